@@ -11,6 +11,28 @@ pub struct Svg<'a> {
     pub hash: &'a [u8],
 }
 
+/// Space added between the glyphs of a [`TextLayout`] when it's drawn
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextSpacing {
+    /// Added after every glyph
+    pub letter_spacing: f64,
+    /// How far each line is moved to the right, by the index of the line in
+    /// [`TextLayout::layout_runs`]. Lines without an offset aren't moved.
+    pub line_offsets: Vec<f64>,
+    /// Added after every white space glyph of each line, by the index of the line
+    pub word_spacings: Vec<f64>,
+}
+
+impl TextSpacing {
+    /// How far the glyph with index `glyph` on line `line` is moved to the right, when `spaces`
+    /// white space glyphs come before it on the line
+    pub fn glyph_offset(&self, line: usize, glyph: usize, spaces: usize) -> f64 {
+        self.line_offsets.get(line).copied().unwrap_or(0.0)
+            + self.letter_spacing * glyph as f64
+            + self.word_spacings.get(line).copied().unwrap_or(0.0) * spaces as f64
+    }
+}
+
 pub trait Renderer {
     fn begin(&mut self);
 
@@ -36,6 +58,15 @@ pub trait Renderer {
     /// [`TextLayout::line_metric`] to get the baseline position of a specific line.
     fn draw_text(&mut self, layout: &TextLayout, pos: impl Into<Point>);
 
+    /// Draw a [`TextLayout`] with extra space between its glyphs, so each glyph is moved to the
+    /// right by [`TextSpacing::glyph_offset`].
+    fn draw_text_with_spacing(
+        &mut self,
+        layout: &TextLayout,
+        pos: impl Into<Point>,
+        spacing: &TextSpacing,
+    );
+
     fn draw_svg<'b>(&mut self, svg: Svg<'b>, rect: Rect, brush: Option<impl Into<BrushRef<'b>>>);
 
     fn finish(&mut self);
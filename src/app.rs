@@ -1,26 +1,81 @@
-use std::{any::Any, collections::HashMap};
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
 use floem_renderer::Renderer;
 use glazier::{
-    kurbo::{Affine, Point, Rect},
-    FileDialogOptions, FileDialogToken, FileInfo, WinHandler,
+    kurbo::{Affine, Point, Rect, Size, Vec2},
+    text::{InputHandler, TextFieldToken},
+    Cursor, FileDialogOptions, FileDialogToken, FileInfo, FormatId, KbKey, KeyEvent, WinHandler,
 };
 use leptos_reactive::{Scope, SignalSet};
 
 use crate::{
+    clipboard::{with_clipboard, ClipboardItem},
     context::{
-        AppState, EventCallback, EventCx, LayoutCx, PaintCx, PaintState, ResizeCallback,
-        ResizeListener, UpdateCx,
+        AppState, DragPayloadFn, DragState, DropAcceptsCallback, DropCallback, DropTarget,
+        EventCallback, EventCx, LayoutCx, PaintCx, PaintState, ResizeCallback, ResizeListener,
+        UpdateCx,
     },
-    event::{Event, EventListner},
+    event::{Event, EventListner, EventPhase},
     ext_event::{EXT_EVENT_HANDLER, WRITE_SIGNALS},
+    gesture::GestureHandler,
     id::{Id, IDPATHS},
-    style::Style,
+    ime::{ImeHandler, ImeSession, TextInputFn, TextInputState},
+    keymap::{KeymapError, KEYMAP},
+    menu::{Menu, MenuAction, MenuPopup, NATIVE_CONTEXT_MENUS},
+    style::{Breakpoint, CursorStyle, Style},
+    stylesheet::StyleSheet,
+    timer::{add_scope_timer, remove_scope_timer, TimerCallback, TimerToken},
     view::{ChangeFlags, View},
 };
 
+/// The view at the end of `id_path`, which starts with the id of `view`
+fn view_by_path<'a>(view: &'a mut dyn View, id_path: &[Id]) -> Option<&'a mut dyn View> {
+    let (id, id_path) = id_path.split_first()?;
+    if *id != view.id() {
+        return None;
+    }
+    match id_path.first() {
+        Some(child) => view_by_path(view.child(*child)?, id_path),
+        None => Some(view),
+    }
+}
+
+/// Paint the view with `id` with its origin at the window position `origin`.
+/// Returns false when the view can't be found.
+fn paint_at(root: &mut dyn View, cx: &mut PaintCx, id: Id, origin: Point) -> bool {
+    let id_path = IDPATHS.with(|paths| paths.borrow().get(&id).cloned());
+    let location = cx.get_layout(id).map(|layout| layout.location);
+    if let (Some(id_path), Some(location)) = (id_path, location) {
+        if let Some(view) = view_by_path(root, &id_path.0) {
+            cx.save();
+            // `paint_main` moves to the location of the view in its parent
+            cx.transform = Affine::translate(
+                origin.to_vec2() - Vec2::new(location.x as f64, location.y as f64),
+            );
+            view.paint_main(cx);
+            cx.restore();
+            return true;
+        }
+    }
+    false
+}
+
+/// Send an event straight to the view with `id`
+fn send_event(view: &mut dyn View, app_state: &mut AppState, id: Id, event: Event) {
+    let id_path = IDPATHS.with(|paths| paths.borrow().get(&id).cloned());
+    if let Some(id_path) = id_path {
+        view.event_main(&mut EventCx::new(app_state), Some(&id_path.0), event);
+    }
+}
+
 thread_local! {
-    static UPDATE_MESSAGES: std::cell::RefCell<Vec<UpdateMessage>> = Default::default();
+    pub(crate) static UPDATE_MESSAGES: std::cell::RefCell<Vec<UpdateMessage>> = Default::default();
     static DEFERRED_UPDATE_MESSAGES: std::cell::RefCell<Vec<(Id, Box<dyn Any>)>> = Default::default();
 }
 
@@ -29,8 +84,18 @@ pub struct App<V: View> {
     handle: glazier::WindowHandle,
     app_state: AppState,
     paint_state: PaintState,
+    cursor: Option<CursorStyle>,
 
     file_dialogs: HashMap<FileDialogToken, Box<dyn Fn(Option<FileInfo>)>>,
+    timers: HashMap<glazier::TimerToken, (TimerToken, Box<TimerCallback>)>,
+    /// The in-app popup menu, when one is open
+    context_menu: Option<MenuPopup>,
+    /// The actions of the items of the last native popup menu, by their ids
+    context_menu_actions: Vec<Option<Box<MenuAction>>>,
+    /// The text field of the window, which the input method of the platform works with
+    text_field: Option<TextFieldToken>,
+    /// The text of the focused text input, shared with the input method
+    ime: Option<Rc<RefCell<ImeSession>>>,
 }
 
 #[derive(Copy, Clone)]
@@ -69,11 +134,155 @@ impl AppContext {
         UPDATE_MESSAGES.with(|msgs| msgs.borrow_mut().push(UpdateMessage::HoverStyle { id, style }));
     }
 
-    pub fn update_event_listner(id: Id, listener: EventListner, action: Box<EventCallback>) {
+    pub fn update_responsive_style(id: Id, breakpoint: Breakpoint, style: Style) {
+        UPDATE_MESSAGES.with(|msgs| {
+            msgs.borrow_mut().push(UpdateMessage::ResponsiveStyle {
+                id,
+                breakpoint,
+                style,
+            })
+        });
+    }
+
+    pub fn update_tab_index(id: Id, tab_index: Option<i32>) {
+        UPDATE_MESSAGES.with(|msgs| {
+            msgs.borrow_mut()
+                .push(UpdateMessage::TabIndex { id, tab_index })
+        });
+    }
+
+    pub fn update_focus_trap(id: Id) {
+        UPDATE_MESSAGES.with(|msgs| msgs.borrow_mut().push(UpdateMessage::FocusTrap(id)));
+    }
+
+    pub fn update_focus_scope(id: Id) {
+        UPDATE_MESSAGES.with(|msgs| msgs.borrow_mut().push(UpdateMessage::FocusScope(id)));
+    }
+
+    pub fn update_draggable(id: Id, payload: Box<DragPayloadFn>) {
+        UPDATE_MESSAGES.with(|msgs| {
+            msgs.borrow_mut()
+                .push(UpdateMessage::Draggable { id, payload })
+        });
+    }
+
+    pub(crate) fn remove_draggable(id: Id) {
+        UPDATE_MESSAGES.with(|msgs| msgs.borrow_mut().push(UpdateMessage::RemoveDraggable(id)));
+    }
+
+    pub fn update_drop_target(
+        id: Id,
+        accepts: Box<DropAcceptsCallback>,
+        on_drop: Box<DropCallback>,
+    ) {
+        UPDATE_MESSAGES.with(|msgs| {
+            msgs.borrow_mut().push(UpdateMessage::DropTarget {
+                id,
+                accepts,
+                on_drop,
+            })
+        });
+    }
+
+    pub(crate) fn remove_drop_target(id: Id) {
+        UPDATE_MESSAGES.with(|msgs| msgs.borrow_mut().push(UpdateMessage::RemoveDropTarget(id)));
+    }
+
+    pub fn update_text_input(id: Id, state: Box<TextInputFn>) {
+        UPDATE_MESSAGES.with(|msgs| {
+            msgs.borrow_mut()
+                .push(UpdateMessage::TextInput { id, state })
+        });
+    }
+
+    pub fn update_gesture(id: Id, handler: GestureHandler) {
+        UPDATE_MESSAGES.with(|msgs| {
+            msgs.borrow_mut()
+                .push(UpdateMessage::Gesture { id, handler })
+        });
+    }
+
+    /// Run `callback` on the UI thread after `duration`. The timer is cancelled when the scope of
+    /// the context is disposed.
+    pub fn request_timer(
+        &self,
+        duration: Duration,
+        callback: impl FnOnce() + 'static,
+    ) -> TimerToken {
+        let token = Self::schedule_timer(duration, Box::new(callback));
+        add_scope_timer(self.scope, token);
+        token
+    }
+
+    /// A timer that isn't tied to a scope
+    pub(crate) fn schedule_timer(duration: Duration, callback: Box<TimerCallback>) -> TimerToken {
+        let token = TimerToken::next();
+        let deadline = Instant::now() + duration;
+        UPDATE_MESSAGES.with(|msgs| {
+            msgs.borrow_mut().push(UpdateMessage::RequestTimer {
+                token,
+                deadline,
+                callback,
+            })
+        });
+        token
+    }
+
+    pub fn cancel_timer(token: TimerToken) {
+        UPDATE_MESSAGES.with(|msgs| msgs.borrow_mut().push(UpdateMessage::CancelTimer(token)));
+    }
+
+    pub(crate) fn update_long_press_timer(id: Id) {
+        UPDATE_MESSAGES.with(|msgs| msgs.borrow_mut().push(UpdateMessage::LongPressTimer(id)));
+    }
+
+    /// Register a command that key bindings can run, replacing any command with the same id
+    pub fn register_command(id: &str, handler: impl Fn() + 'static) {
+        KEYMAP.with(|keymap| keymap.borrow_mut().register_command(id, handler));
+    }
+
+    /// Bind keys like `Ctrl+K Ctrl+S` to a command, only while the focus is in the `scope` view
+    /// if there is one. See [`keymap`](crate::keymap) for how bindings are written.
+    pub fn bind_key(keys: &str, command: &str, scope: Option<Id>) -> Result<(), KeymapError> {
+        KEYMAP.with(|keymap| keymap.borrow_mut().bind(keys, command, scope))
+    }
+
+    pub fn unbind_command(command: &str) {
+        KEYMAP.with(|keymap| keymap.borrow_mut().unbind(command));
+    }
+
+    /// Run a command as if its keys were pressed. Returns false if there is no such command.
+    pub fn run_command(id: &str) -> bool {
+        let handler = KEYMAP.with(|keymap| keymap.borrow().command(id));
+        if let Some(handler) = handler.as_ref() {
+            handler();
+        }
+        handler.is_some()
+    }
+
+    pub fn update_classes(id: Id, classes: Vec<String>) {
+        UPDATE_MESSAGES.with(|msgs| msgs.borrow_mut().push(UpdateMessage::Classes { id, classes }));
+    }
+
+    /// Replace the stylesheet whose rules apply to the views with classes
+    pub fn update_stylesheet(stylesheet: StyleSheet) {
+        UPDATE_MESSAGES.with(|msgs| {
+            msgs.borrow_mut()
+                .push(UpdateMessage::StyleSheet(stylesheet))
+        });
+    }
+
+    pub fn update_event_listner(
+        id: Id,
+        listener: EventListner,
+        phase: EventPhase,
+        action: Box<EventCallback>,
+    ) {
         UPDATE_MESSAGES.with(|msgs| {
             msgs.borrow_mut().push(UpdateMessage::EventListener {
                 id,
                 listener,
+                phase,
                 action,
             })
         });
@@ -98,6 +307,34 @@ impl AppContext {
         });
     }
 
+    /// Put plain text on the clipboard
+    pub fn copy_text(text: &str) {
+        with_clipboard(|clipboard| clipboard.put_string(text));
+    }
+
+    /// The plain text on the clipboard
+    pub fn paste_text() -> Option<String> {
+        with_clipboard(|clipboard| clipboard.get_string())
+    }
+
+    /// Put the same data in several formats on the clipboard, like HTML along with plain text
+    pub fn copy_formats(items: &[ClipboardItem]) {
+        with_clipboard(|clipboard| clipboard.put_formats(items));
+    }
+
+    /// The data in the first of `formats` that is on the clipboard
+    pub fn paste_format(formats: &[FormatId]) -> Option<ClipboardItem> {
+        with_clipboard(|clipboard| clipboard.get_format(formats))
+    }
+
+    /// Show `menu` as a popup menu at the window position `pos`
+    pub fn show_context_menu(menu: Menu, pos: Point) {
+        UPDATE_MESSAGES.with(|msgs| {
+            msgs.borrow_mut()
+                .push(UpdateMessage::ContextMenu { menu, pos })
+        });
+    }
+
     pub fn with_id(mut self, id: Id) -> Self {
         self.id = id;
         self
@@ -110,7 +347,40 @@ impl AppContext {
 
 pub enum UpdateMessage {
     Focus(Id),
+    TabIndex {
+        id: Id,
+        tab_index: Option<i32>,
+    },
+    FocusTrap(Id),
+    FocusScope(Id),
+    Draggable {
+        id: Id,
+        payload: Box<DragPayloadFn>,
+    },
+    RemoveDraggable(Id),
+    DropTarget {
+        id: Id,
+        accepts: Box<DropAcceptsCallback>,
+        on_drop: Box<DropCallback>,
+    },
+    RemoveDropTarget(Id),
+    Gesture {
+        id: Id,
+        handler: GestureHandler,
+    },
+    TextInput {
+        id: Id,
+        state: Box<TextInputFn>,
+    },
+    /// The time for a long press on the view is up
+    LongPressTimer(Id),
     RequestPaint,
+    RequestTimer {
+        token: TimerToken,
+        deadline: Instant,
+        callback: Box<TimerCallback>,
+    },
+    CancelTimer(TimerToken),
     State {
         id: Id,
         state: Box<dyn Any>,
@@ -123,9 +393,20 @@ pub enum UpdateMessage {
         id: Id,
         style: Style,
     },
+    ResponsiveStyle {
+        id: Id,
+        breakpoint: Breakpoint,
+        style: Style,
+    },
+    Classes {
+        id: Id,
+        classes: Vec<String>,
+    },
+    StyleSheet(StyleSheet),
     EventListener {
         id: Id,
         listener: EventListner,
+        phase: EventPhase,
         action: Box<EventCallback>,
     },
     ResizeListener {
@@ -136,6 +417,10 @@ pub enum UpdateMessage {
         options: FileDialogOptions,
         file_info_action: Box<dyn Fn(Option<FileInfo>)>,
     },
+    ContextMenu {
+        menu: Menu,
+        pos: Point,
+    },
 }
 
 impl<V: View> App<V> {
@@ -151,32 +436,33 @@ impl<V: View> App<V> {
             app_state: AppState::new(),
             paint_state: PaintState::new(),
             handle: Default::default(),
+            cursor: None,
 
             file_dialogs: HashMap::new(),
+            timers: HashMap::new(),
+            context_menu: None,
+            context_menu_actions: Vec::new(),
+            text_field: None,
+            ime: None,
         }
     }
 
     fn layout(&mut self) {
-        let mut cx = LayoutCx {
-            app_state: &mut self.app_state,
-            viewport: None,
-            font_size: None,
-            font_family: None,
-            font_weight: None,
-            font_style: None,
-            window_origin: Point::ZERO,
-            saved_viewports: Vec::new(),
-            saved_font_sizes: Vec::new(),
-            saved_font_families: Vec::new(),
-            saved_font_weights: Vec::new(),
-            saved_font_styles: Vec::new(),
-            saved_window_origins: Vec::new(),
-        };
+        let mut cx = LayoutCx::new(&mut self.app_state);
         cx.app_state.root = Some(self.view.layout_main(&mut cx));
         cx.app_state.compute_layout();
 
         cx.clear();
         self.view.compute_layout_main(&mut cx);
+        cx.app_state.request_compute_layout = false;
+    }
+
+    /// Compute where the views are in the window again, for when they moved without their layout
+    /// changing, like when their parent scrolled
+    fn compute_layout(&mut self) {
+        let mut cx = LayoutCx::new(&mut self.app_state);
+        self.view.compute_layout_main(&mut cx);
+        cx.app_state.request_compute_layout = false;
     }
 
     pub fn paint(&mut self) {
@@ -190,6 +476,11 @@ impl<V: View> App<V> {
             font_family: None,
             font_weight: None,
             font_style: None,
+            line_height: None,
+            letter_spacing: None,
+            text_align: None,
+            white_space: None,
+            text_overflow: None,
             saved_transforms: Vec::new(),
             saved_clips: Vec::new(),
             saved_colors: Vec::new(),
@@ -197,9 +488,34 @@ impl<V: View> App<V> {
             saved_font_families: Vec::new(),
             saved_font_weights: Vec::new(),
             saved_font_styles: Vec::new(),
+            saved_line_heights: Vec::new(),
+            saved_letter_spacings: Vec::new(),
+            saved_text_aligns: Vec::new(),
+            saved_white_spaces: Vec::new(),
+            saved_text_overflows: Vec::new(),
         };
         cx.paint_state.renderer.as_mut().unwrap().begin();
         self.view.paint_main(&mut cx);
+        let overlays = cx.app_state.overlays.clone();
+        for (id, origin) in overlays {
+            if !paint_at(&mut self.view, &mut cx, id, origin) {
+                // The view is gone
+                cx.app_state.hide_overlay(id);
+            }
+        }
+        if let Some(menu) = self.context_menu.as_ref() {
+            menu.paint(&mut cx);
+        }
+        // The dragged view is painted again on top of everything, following the mouse
+        let preview = cx
+            .app_state
+            .drag
+            .as_ref()
+            .filter(|drag| drag.is_started())
+            .map(|drag| (drag.source, drag.pos - drag.offset.to_vec2()));
+        if let Some((source, origin)) = preview {
+            paint_at(&mut self.view, &mut cx, source, origin);
+        }
         cx.paint_state.renderer.as_mut().unwrap().finish();
     }
 
@@ -239,8 +555,74 @@ impl<V: View> App<V> {
                     UpdateMessage::RequestPaint => {
                         flags |= ChangeFlags::PAINT;
                     }
+                    UpdateMessage::RequestTimer {
+                        token,
+                        deadline,
+                        callback,
+                    } => {
+                        let timer = self
+                            .handle
+                            .request_timer(deadline.saturating_duration_since(Instant::now()));
+                        self.timers.insert(timer, (token, callback));
+                    }
+                    UpdateMessage::CancelTimer(token) => {
+                        self.timers.retain(|_, (t, _)| *t != token);
+                        remove_scope_timer(token);
+                    }
                     UpdateMessage::Focus(id) => {
-                        cx.app_state.focus = Some(id);
+                        let old = cx.app_state.focus;
+                        if old != Some(id) {
+                            cx.app_state.update_focus(id);
+                            if let Some(old) = old {
+                                send_event(&mut self.view, cx.app_state, old, Event::FocusLost);
+                            }
+                            send_event(&mut self.view, cx.app_state, id, Event::FocusGained);
+                        }
+                    }
+                    UpdateMessage::TabIndex { id, tab_index } => {
+                        let state = cx.app_state.view_state(id);
+                        state.tab_index = tab_index;
+                    }
+                    UpdateMessage::FocusTrap(id) => {
+                        let state = cx.app_state.view_state(id);
+                        state.focus_trap = true;
+                    }
+                    UpdateMessage::FocusScope(id) => {
+                        let state = cx.app_state.view_state(id);
+                        state.focus_scope = true;
+                    }
+                    UpdateMessage::Gesture { id, handler } => {
+                        let state = cx.app_state.view_state(id);
+                        state.gestures.set_handler(handler);
+                    }
+                    UpdateMessage::TextInput { id, state } => {
+                        cx.app_state.view_state(id).text_input = Some(state);
+                    }
+                    UpdateMessage::LongPressTimer(id) => {
+                        let state = cx.app_state.view_state(id);
+                        state.gestures.check_long_press(Instant::now());
+                    }
+                    UpdateMessage::Draggable { id, payload } => {
+                        let state = cx.app_state.view_state(id);
+                        state.draggable = Some(payload);
+                    }
+                    UpdateMessage::RemoveDraggable(id) => {
+                        if let Some(state) = cx.app_state.view_states.get_mut(&id) {
+                            state.draggable = None;
+                        }
+                    }
+                    UpdateMessage::DropTarget {
+                        id,
+                        accepts,
+                        on_drop,
+                    } => {
+                        let state = cx.app_state.view_state(id);
+                        state.drop_target = Some(DropTarget { accepts, on_drop });
+                    }
+                    UpdateMessage::RemoveDropTarget(id) => {
+                        if let Some(state) = cx.app_state.view_states.get_mut(&id) {
+                            state.drop_target = None;
+                        }
                     }
                     UpdateMessage::State { id, state } => {
                         let id_path = IDPATHS.with(|paths| paths.borrow().get(&id).cloned());
@@ -251,19 +633,41 @@ impl<V: View> App<V> {
                     UpdateMessage::Style { id, style } => {
                         let state = cx.app_state.view_state(id);
                         state.style = style;
+                        state.request_style = true;
                         cx.request_layout(id);
                     }
                     UpdateMessage::HoverStyle { id, style } => {
                         let state = cx.app_state.view_state(id);
                         state.hover_style = Some(style);
+                        state.request_style = true;
+                        cx.request_layout(id);
+                    }
+                    UpdateMessage::ResponsiveStyle {
+                        id,
+                        breakpoint,
+                        style,
+                    } => {
+                        cx.app_state.set_responsive_style(id, breakpoint, style);
+                    }
+                    UpdateMessage::Classes { id, classes } => {
+                        let state = cx.app_state.view_state(id);
+                        state.classes = classes;
+                        state.request_style = true;
+                        cx.request_layout(id);
+                    }
+                    UpdateMessage::StyleSheet(stylesheet) => {
+                        cx.app_state.set_stylesheet(stylesheet);
                     }
                     UpdateMessage::EventListener {
                         id,
                         listener,
+                        phase,
                         action,
                     } => {
                         let state = cx.app_state.view_state(id);
-                        state.event_listeners.insert(listener, action);
+                        state
+                            .event_listeners
+                            .insert((listener, phase), action.into());
                     }
                     UpdateMessage::ResizeListener { id, action } => {
                         let state = cx.app_state.view_state(id);
@@ -282,6 +686,17 @@ impl<V: View> App<V> {
                             self.file_dialogs.insert(token, file_info_action);
                         }
                     }
+                    UpdateMessage::ContextMenu { menu, pos } => {
+                        if NATIVE_CONTEXT_MENUS {
+                            self.context_menu_actions.clear();
+                            let menu = menu.platform_menu(&mut self.context_menu_actions);
+                            self.handle.show_context_menu(menu, pos);
+                        } else {
+                            self.context_menu =
+                                Some(MenuPopup::new(menu, pos, cx.app_state.root_size));
+                            flags |= ChangeFlags::PAINT;
+                        }
+                    }
                 }
             }
         }
@@ -300,46 +715,300 @@ impl<V: View> App<V> {
         let mut flags = ChangeFlags::empty();
         loop {
             flags |= self.process_update_messages();
-            if !self.needs_layout() && !self.has_deferred_update_messages() {
+            if self.needs_layout() || self.has_deferred_update_messages() {
+                flags |= ChangeFlags::LAYOUT;
+                self.layout();
+                flags |= self.process_deferred_update_messages();
+            } else if self.app_state.request_compute_layout {
+                flags |= ChangeFlags::PAINT;
+                self.compute_layout();
+            } else {
                 break;
             }
-            flags |= ChangeFlags::LAYOUT;
-            self.layout();
-            flags |= self.process_deferred_update_messages();
         }
 
         if !flags.is_empty() {
             self.handle.invalidate();
         }
+        self.update_ime();
     }
 
-    pub fn event(&mut self, event: Event) {
-        let mut cx = EventCx {
-            app_state: &mut self.app_state,
-        };
+    /// Returns whether the event was used
+    pub fn event(&mut self, event: Event) -> bool {
+        if self.context_menu_event(&event) {
+            self.process_update();
+            return true;
+        }
+        if self.drag_event(&event) {
+            self.process_update();
+            return true;
+        }
+        if let Event::KeyDown(key_event) = &event {
+            if self.keymap_key_down(key_event) {
+                self.process_update();
+                return true;
+            }
+        }
+
+        let mut cx = EventCx::new(&mut self.app_state);
+        let mut handled = false;
         if event.needs_focus() {
             if let Some(id) = cx.app_state.focus {
                 IDPATHS.with(|paths| {
                     if let Some(id_path) = paths.borrow().get(&id) {
-                        self.view.event_main(&mut cx, Some(&id_path.0), event);
+                        handled = self
+                            .view
+                            .event_main(&mut cx, Some(&id_path.0), event.clone());
                     }
                 });
             }
+            if cx.default_prevented {
+                handled = true;
+            } else if !handled {
+                if let Event::KeyDown(event) = &event {
+                    handled = self.focus_key_down(event);
+                }
+            }
         } else if cx.app_state.active.is_some() && event.is_mouse() {
             let id = cx.app_state.active.unwrap();
             IDPATHS.with(|paths| {
                 if let Some(id_path) = paths.borrow().get(&id) {
-                    self.view
+                    handled = self
+                        .view
                         .event_main(&mut cx, Some(&id_path.0), event.clone());
                 }
             });
             if let Event::MouseUp(_) = &event {
                 self.app_state.active = None;
+                self.app_state.request_style_update(id);
             }
         } else {
-            self.view.event_main(&mut cx, None, event);
+            handled = self.view.event_main(&mut cx, None, event.clone());
+            if matches!(event, Event::MouseDown(_)) && !cx.default_prevented {
+                // Clicking a focusable view focuses it
+                if let Some(id) = self.app_state.hovered_focusable() {
+                    UPDATE_MESSAGES.with(|msgs| msgs.borrow_mut().push(UpdateMessage::Focus(id)));
+                }
+            }
+        }
+        if let Some((source, offset)) = self.app_state.drag_start.take() {
+            if let Event::MouseDown(mouse_event) = &event {
+                self.app_state.drag = Some(DragState {
+                    source,
+                    offset,
+                    start: mouse_event.pos,
+                    pos: mouse_event.pos,
+                    payload: None,
+                    over: None,
+                });
+            }
         }
         self.process_update();
+        handled
+    }
+
+    /// An open popup menu gets the mouse and keyboard events. Returns true when the event went
+    /// to the menu.
+    fn context_menu_event(&mut self, event: &Event) -> bool {
+        let menu = match self.context_menu.as_mut() {
+            Some(menu) => menu,
+            None => return false,
+        };
+        if !menu.event(event) {
+            self.context_menu = None;
+        }
+        self.handle.invalidate();
+        event.is_mouse() || matches!(event, Event::KeyDown(_) | Event::KeyUp(_))
+    }
+
+    /// Start, move or drop a drag of a view the mouse was pressed on. Returns true when the
+    /// event was used for the drag.
+    fn drag_event(&mut self, event: &Event) -> bool {
+        let drag = match self.app_state.drag.as_mut() {
+            Some(drag) => drag,
+            None => return false,
+        };
+        match event {
+            Event::MouseMove(mouse_event) => {
+                drag.pos = mouse_event.pos;
+                if !drag.is_started() {
+                    if !drag.passed_threshold() {
+                        return false;
+                    }
+                    let source = drag.source;
+                    drag.payload = self
+                        .app_state
+                        .view_states
+                        .get(&source)
+                        .and_then(|view| view.draggable.as_ref())
+                        .map(|payload| payload());
+                    // The dragged view gets the mouse events until it is dropped
+                    self.app_state.update_active(source);
+                }
+
+                let old_over = self
+                    .app_state
+                    .drag
+                    .as_mut()
+                    .and_then(|drag| drag.over.take());
+                self.view.event_main(
+                    &mut EventCx::new(&mut self.app_state),
+                    None,
+                    Event::DragOver(mouse_event.clone()),
+                );
+                let over = self.app_state.drag.as_ref().and_then(|drag| drag.over);
+                if over != old_over {
+                    if let Some(old_over) = old_over {
+                        send_event(
+                            &mut self.view,
+                            &mut self.app_state,
+                            old_over,
+                            Event::DragLeave,
+                        );
+                    }
+                    if let Some(over) = over {
+                        send_event(&mut self.view, &mut self.app_state, over, Event::DragEnter);
+                    }
+                }
+                self.handle.invalidate();
+                true
+            }
+            Event::MouseUp(mouse_event) => {
+                if !drag.is_started() {
+                    self.app_state.drag = None;
+                    return false;
+                }
+                self.view.event_main(
+                    &mut EventCx::new(&mut self.app_state),
+                    None,
+                    Event::Drop(mouse_event.clone()),
+                );
+                self.end_drag();
+                true
+            }
+            Event::KeyDown(key_event) if key_event.key == KbKey::Escape && drag.is_started() => {
+                self.end_drag();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Stop dragging, after a drop or when the drag is cancelled
+    fn end_drag(&mut self) {
+        if let Some(drag) = self.app_state.drag.take() {
+            if let Some(over) = drag.over {
+                send_event(&mut self.view, &mut self.app_state, over, Event::DragLeave);
+            }
+            self.app_state.active = None;
+            self.app_state.request_style_update(drag.source);
+        }
+        self.update_cursor();
+        self.handle.invalidate();
+    }
+
+    /// Run the command bound to the keys, before the key press goes to the focused view. Returns
+    /// true when the press was part of a key binding.
+    fn keymap_key_down(&mut self, event: &KeyEvent) -> bool {
+        let focus_path = self
+            .app_state
+            .focus
+            .and_then(|id| IDPATHS.with(|paths| paths.borrow().get(&id).cloned()))
+            .map(|id_path| id_path.0)
+            .unwrap_or_default();
+        let (used, handler) =
+            KEYMAP.with(|keymap| keymap.borrow_mut().key_down(event, &focus_path));
+        if let Some(handler) = handler {
+            handler();
+        }
+        used
+    }
+
+    /// Move the focus with Tab, Shift+Tab and the arrow keys, when the focused view didn't use
+    /// the key
+    fn focus_key_down(&mut self, event: &KeyEvent) -> bool {
+        let next = match event.key {
+            KbKey::Tab => self.app_state.next_tab_focus(event.mods.shift()),
+            KbKey::ArrowDown | KbKey::ArrowRight => self.app_state.next_arrow_focus(false),
+            KbKey::ArrowUp | KbKey::ArrowLeft => self.app_state.next_arrow_focus(true),
+            _ => None,
+        };
+        if let Some(id) = next {
+            UPDATE_MESSAGES.with(|msgs| msgs.borrow_mut().push(UpdateMessage::Focus(id)));
+        }
+        next.is_some()
+    }
+
+    /// Keep the input method on the focused view when it is a text input, and tell it when the
+    /// text input changed
+    fn update_ime(&mut self) {
+        let token = match self.text_field {
+            Some(token) => token,
+            None => return,
+        };
+        let input = self.app_state.focus.and_then(|id| {
+            let view = self.app_state.view_states.get(&id)?;
+            let state = (view.text_input.as_ref()?)();
+            let size = self
+                .app_state
+                .get_layout(id)
+                .map(|layout| Size::new(layout.size.width as f64, layout.size.height as f64));
+            let rect = size
+                .unwrap_or_default()
+                .to_rect()
+                .with_origin(view.window_origin);
+            Some((id, state, rect))
+        });
+        match (input, self.ime.as_ref()) {
+            (Some((id, state, rect)), Some(ime)) if ime.borrow().id == id => {
+                let update = ime.borrow_mut().update(state, rect);
+                if let Some(update) = update {
+                    self.handle.update_text_field(token, update);
+                }
+            }
+            (Some((id, state, rect)), _) => {
+                self.ime = Some(Rc::new(RefCell::new(ImeSession::new(id, state, rect))));
+                self.handle.set_focused_text_field(Some(token));
+                self.handle
+                    .update_text_field(token, glazier::text::Event::Reset);
+            }
+            (None, Some(_)) => {
+                self.ime = None;
+                self.handle.set_focused_text_field(None);
+            }
+            (None, None) => {}
+        }
+    }
+
+    /// Show the cursor of the hovered views
+    fn update_cursor(&mut self) {
+        let dragging = self
+            .app_state
+            .drag
+            .as_ref()
+            .map(|drag| drag.is_started())
+            .unwrap_or(false);
+        let cursor = if dragging {
+            Some(CursorStyle::Grabbing)
+        } else {
+            self.app_state.hovered_cursor()
+        };
+        if cursor == self.cursor {
+            return;
+        }
+        self.cursor = cursor;
+        let cursor = match cursor.unwrap_or_default() {
+            CursorStyle::Default => Cursor::Arrow,
+            CursorStyle::Pointer => Cursor::Pointer,
+            CursorStyle::Text => Cursor::IBeam,
+            // There are no native grab cursors in glazier yet
+            CursorStyle::Grab | CursorStyle::Grabbing => Cursor::Pointer,
+            CursorStyle::ColResize => Cursor::ResizeLeftRight,
+            CursorStyle::RowResize => Cursor::ResizeUpDown,
+            CursorStyle::NotAllowed => Cursor::NotAllowed,
+            CursorStyle::Crosshair => Cursor::Crosshair,
+        };
+        self.handle.set_cursor(&cursor);
     }
 
     fn idle(&mut self) {
@@ -362,6 +1031,7 @@ impl<V: View> WinHandler for App<V> {
         if let Some(idle_handle) = handle.get_idle_handle() {
             *EXT_EVENT_HANDLER.handle.lock() = Some(idle_handle);
         }
+        self.text_field = Some(handle.add_text_field());
         self.idle();
     }
 
@@ -370,6 +1040,8 @@ impl<V: View> WinHandler for App<V> {
         let scale = self.handle.get_scale().unwrap_or_default();
         self.paint_state.resize(scale, size);
         self.app_state.set_root_size(size);
+        // Viewport relative lengths need to be resolved again
+        self.app_state.request_layout(self.view.id());
         self.layout();
         self.process_update();
         self.handle.invalidate();
@@ -382,8 +1054,13 @@ impl<V: View> WinHandler for App<V> {
     }
 
     fn key_down(&mut self, event: glazier::KeyEvent) -> bool {
-        self.event(Event::KeyDown(event));
-        true
+        let handled = self.event(Event::KeyDown(event));
+        // The input method of a focused text input turns the keys no view used into text
+        handled || self.ime.is_none()
+    }
+
+    fn key_up(&mut self, event: glazier::KeyEvent) {
+        self.event(Event::KeyUp(event));
     }
 
     fn mouse_down(&mut self, event: &glazier::MouseEvent) {
@@ -396,20 +1073,77 @@ impl<V: View> WinHandler for App<V> {
 
     fn mouse_move(&mut self, event: &glazier::MouseEvent) {
         self.event(Event::MouseMove(event.clone()));
+        self.update_cursor();
     }
 
     fn mouse_wheel(&mut self, event: &glazier::MouseEvent) {
         self.event(Event::MouseWheel(event.clone()));
     }
 
+    fn mouse_leave(&mut self) {
+        self.event(Event::WindowMouseLeave);
+        self.update_cursor();
+    }
+
+    fn got_focus(&mut self) {
+        self.event(Event::WindowGotFocus);
+    }
+
+    fn lost_focus(&mut self) {
+        self.event(Event::WindowLostFocus);
+    }
+
     fn idle(&mut self, _token: glazier::IdleToken) {
         self.idle();
     }
 
+    fn acquire_input_lock(
+        &mut self,
+        _token: TextFieldToken,
+        _mutable: bool,
+    ) -> Box<dyn InputHandler> {
+        let ime = self.ime.clone().unwrap_or_else(|| {
+            Rc::new(RefCell::new(ImeSession::new(
+                self.view.id(),
+                TextInputState::default(),
+                Rect::ZERO,
+            )))
+        });
+        Box::new(ImeHandler(ime))
+    }
+
+    fn release_input_lock(&mut self, _token: TextFieldToken) {
+        if let Some(ime) = self.ime.clone() {
+            let (id, events) = {
+                let mut ime = ime.borrow_mut();
+                (ime.id, ime.take_events())
+            };
+            for event in events {
+                send_event(&mut self.view, &mut self.app_state, id, event);
+            }
+            self.process_update();
+        }
+    }
+
+    fn timer(&mut self, token: glazier::TimerToken) {
+        if let Some((token, callback)) = self.timers.remove(&token) {
+            remove_scope_timer(token);
+            callback();
+            self.process_update();
+        }
+    }
+
     fn as_any(&mut self) -> &mut dyn Any {
         todo!()
     }
 
+    fn command(&mut self, id: u32) {
+        if let Some(Some(action)) = self.context_menu_actions.get(id as usize) {
+            action();
+            self.process_update();
+        }
+    }
+
     fn open_file(&mut self, token: FileDialogToken, file: Option<FileInfo>) {
         if let Some(action) = self.file_dialogs.remove(&token) {
             action(file);
@@ -0,0 +1,134 @@
+//! # Clipboard
+//! [`AppContext::copy_text`](crate::app::AppContext::copy_text) and the other clipboard functions
+//! of the context go through a [`ClipboardProvider`]. It is the clipboard of the platform by
+//! default, and tests and headless runs can use a [`MemoryClipboard`] with [`set_clipboard`].
+
+use std::cell::RefCell;
+
+use glazier::{Application, ClipboardFormat, FormatId};
+
+/// Data in one format, like `text/html`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardItem {
+    pub format: FormatId,
+    pub data: Vec<u8>,
+}
+
+pub trait ClipboardProvider {
+    /// Replace the contents of the clipboard with plain text
+    fn put_string(&mut self, text: &str);
+
+    fn get_string(&self) -> Option<String>;
+
+    /// Replace the contents of the clipboard with the same data in several formats
+    fn put_formats(&mut self, items: &[ClipboardItem]);
+
+    /// The data in the first of `formats` that the clipboard has
+    fn get_format(&self, formats: &[FormatId]) -> Option<ClipboardItem>;
+}
+
+/// The clipboard of the platform, through glazier
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClipboard;
+
+impl ClipboardProvider for SystemClipboard {
+    fn put_string(&mut self, text: &str) {
+        Application::global().clipboard().put_string(text);
+    }
+
+    fn get_string(&self) -> Option<String> {
+        Application::global().clipboard().get_string()
+    }
+
+    fn put_formats(&mut self, items: &[ClipboardItem]) {
+        let formats: Vec<ClipboardFormat> = items
+            .iter()
+            .map(|item| ClipboardFormat::new(item.format, item.data.clone()))
+            .collect();
+        Application::global().clipboard().put_formats(&formats);
+    }
+
+    fn get_format(&self, formats: &[FormatId]) -> Option<ClipboardItem> {
+        let clipboard = Application::global().clipboard();
+        let format = clipboard.preferred_format(formats)?;
+        let data = clipboard.get_format(format)?;
+        Some(ClipboardItem { format, data })
+    }
+}
+
+/// A clipboard that only the app sees
+#[derive(Debug, Clone, Default)]
+pub struct MemoryClipboard {
+    items: Vec<ClipboardItem>,
+}
+
+impl ClipboardProvider for MemoryClipboard {
+    fn put_string(&mut self, text: &str) {
+        self.items = vec![ClipboardItem {
+            format: ClipboardFormat::TEXT,
+            data: text.as_bytes().to_vec(),
+        }];
+    }
+
+    fn get_string(&self) -> Option<String> {
+        let item = self.get_format(&[ClipboardFormat::TEXT])?;
+        String::from_utf8(item.data).ok()
+    }
+
+    fn put_formats(&mut self, items: &[ClipboardItem]) {
+        self.items = items.to_vec();
+    }
+
+    fn get_format(&self, formats: &[FormatId]) -> Option<ClipboardItem> {
+        formats
+            .iter()
+            .find_map(|format| self.items.iter().find(|item| item.format == *format))
+            .cloned()
+    }
+}
+
+thread_local! {
+    static CLIPBOARD: RefCell<Box<dyn ClipboardProvider>> = RefCell::new(Box::new(SystemClipboard));
+}
+
+/// Copy and paste with `clipboard` on this thread from now on
+pub fn set_clipboard(clipboard: impl ClipboardProvider + 'static) {
+    CLIPBOARD.with(|current| *current.borrow_mut() = Box::new(clipboard));
+}
+
+pub(crate) fn with_clipboard<R>(f: impl FnOnce(&mut dyn ClipboardProvider) -> R) -> R {
+    CLIPBOARD.with(|clipboard| f(clipboard.borrow_mut().as_mut()))
+}
+
+#[cfg(test)]
+mod tests {
+    use glazier::ClipboardFormat;
+
+    use super::{set_clipboard, ClipboardItem, MemoryClipboard};
+    use crate::app::AppContext;
+
+    #[test]
+    fn copy_and_paste_with_memory_clipboard() {
+        set_clipboard(MemoryClipboard::default());
+        assert_eq!(AppContext::paste_text(), None);
+
+        AppContext::copy_text("id-42");
+        assert_eq!(AppContext::paste_text().as_deref(), Some("id-42"));
+
+        let html = ClipboardItem {
+            format: "text/html",
+            data: b"<b>id-42</b>".to_vec(),
+        };
+        let text = ClipboardItem {
+            format: ClipboardFormat::TEXT,
+            data: b"id-42".to_vec(),
+        };
+        AppContext::copy_formats(&[html.clone(), text]);
+        assert_eq!(
+            AppContext::paste_format(&["image/png", "text/html"]),
+            Some(html)
+        );
+        assert_eq!(AppContext::paste_format(&["image/png"]), None);
+        assert_eq!(AppContext::paste_text().as_deref(), Some("id-42"));
+    }
+}
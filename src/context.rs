@@ -1,6 +1,8 @@
 use std::{
+    any::Any,
     collections::{HashMap, HashSet},
     ops::{Deref, DerefMut},
+    rc::Rc,
 };
 
 use floem_renderer::{
@@ -13,18 +15,60 @@ use glazier::{
 };
 use taffy::{
     prelude::{Layout, Node},
-    style::{AvailableSpace, Display},
+    style::{AvailableSpace, Display, LengthPercentage},
 };
 use vello::peniko::Color;
 
 use crate::{
-    event::{Event, EventListner},
-    id::Id,
-    style::{ReifiedStyle, Style},
+    app::AppContext,
+    event::{Event, EventListner, EventPhase},
+    gesture::{Gestures, DRAG_THRESHOLD},
+    id::{Id, IDPATHS},
+    ime::TextInputFn,
+    style::{
+        Breakpoint, CursorStyle, LengthContext, Overflow, ReifiedStyle, Style, TextAlign,
+        TextOverflow, WhiteSpace, DEFAULT_FONT_SIZE,
+    },
+    stylesheet::StyleSheet,
+    view::View,
 };
 
-pub type EventCallback = dyn Fn(&Event) -> bool;
+pub type EventCallback = dyn Fn(&mut EventCx, &Event);
 pub type ResizeCallback = dyn Fn(Point, Rect);
+pub type DragPayloadFn = dyn Fn() -> Box<dyn Any>;
+pub type DropAcceptsCallback = dyn Fn(&dyn Any) -> bool;
+pub type DropCallback = dyn Fn(Box<dyn Any>, Point);
+
+pub(crate) struct DropTarget {
+    pub(crate) accepts: Box<DropAcceptsCallback>,
+    pub(crate) on_drop: Box<DropCallback>,
+}
+
+/// A view being dragged with the mouse
+pub(crate) struct DragState {
+    pub(crate) source: Id,
+    /// Where in the source view it was grabbed
+    pub(crate) offset: Point,
+    /// The window position the mouse was pressed at
+    pub(crate) start: Point,
+    /// The window position of the mouse
+    pub(crate) pos: Point,
+    /// The payload of the source, which is only taken once the mouse moved far enough to start
+    /// the drag
+    pub(crate) payload: Option<Box<dyn Any>>,
+    /// The drop target under the mouse that accepts the payload
+    pub(crate) over: Option<Id>,
+}
+
+impl DragState {
+    pub(crate) fn is_started(&self) -> bool {
+        self.payload.is_some()
+    }
+
+    pub(crate) fn passed_threshold(&self) -> bool {
+        (self.pos - self.start).hypot() >= DRAG_THRESHOLD
+    }
+}
 
 pub(crate) struct ResizeListener {
     pub(crate) window_origin: Point,
@@ -40,8 +84,34 @@ pub struct ViewState {
     pub(crate) style: Style,
     pub(crate) hover_style: Option<Style>,
     pub(crate) reified_style: Option<ReifiedStyle>,
-    pub(crate) event_listeners: HashMap<EventListner, Box<EventCallback>>,
+    /// The reified style is out of date with the styles of the view
+    pub(crate) request_style: bool,
+    /// The interaction state the reified style was derived for
+    pub(crate) reified_interact_state: InteractionState,
+    /// What the lengths in the style were last resolved against
+    pub(crate) length_context: Option<LengthContext>,
+    pub(crate) event_listeners: HashMap<(EventListner, EventPhase), Rc<EventCallback>>,
     pub(crate) resize_listener: Option<ResizeListener>,
+    /// The stylesheet classes of the view
+    pub(crate) classes: Vec<String>,
+    /// How far the content of a view with scrollable overflow is scrolled
+    pub(crate) scroll_offset: Vec2,
+    /// Styles that apply on top of `style` while the window width is in the breakpoint
+    pub(crate) responsive_styles: Vec<(Breakpoint, Style)>,
+    /// The tab order of a view that can be focused, like HTML `tabindex`
+    pub(crate) tab_index: Option<i32>,
+    /// Tab navigation stays within the view while it is shown
+    pub(crate) focus_trap: bool,
+    /// The arrow keys move the focus between the focusable views inside
+    pub(crate) focus_scope: bool,
+    /// Gives the payload when the view is dragged
+    pub(crate) draggable: Option<Box<DragPayloadFn>>,
+    pub(crate) drop_target: Option<DropTarget>,
+    pub(crate) gestures: Gestures,
+    /// Where the view is in the window, as of the last layout
+    pub(crate) window_origin: Point,
+    /// Gives the text of a view that takes text input, for the input method
+    pub(crate) text_input: Option<Box<TextInputFn>>,
 }
 
 impl ViewState {
@@ -53,31 +123,71 @@ impl ViewState {
             style: Style::default(),
             hover_style: None,
             reified_style: None,
+            request_style: true,
+            reified_interact_state: InteractionState::default(),
+            length_context: None,
             children_nodes: Vec::new(),
             event_listeners: HashMap::new(),
             resize_listener: None,
+            classes: Vec::new(),
+            scroll_offset: Vec2::ZERO,
+            responsive_styles: Vec::new(),
+            tab_index: None,
+            focus_trap: false,
+            focus_scope: false,
+            draggable: None,
+            drop_target: None,
+            gestures: Gestures::default(),
+            window_origin: Point::ZERO,
+            text_input: None,
         }
     }
 
-    // TODO: the held reified style could be a cache, so this could be `&self`
-    pub(crate) fn fill_reified_style(
+    /// The reified style is cached, and only derived again when the styles of the view were
+    /// changed or the interaction state is different. Returns whether the text properties that
+    /// the children inherit changed, which means their text has to be laid out again.
+    fn fill_reified_style(
         &mut self,
         interact_state: InteractionState,
-        view_style: &ReifiedStyle,
-    ) -> &ReifiedStyle {
-        let base_style = self.style.clone().reify(view_style);
-        self.reified_style = if interact_state.is_hovered {
-            Some(
+        view_style: impl FnOnce() -> ReifiedStyle,
+        stylesheet: &StyleSheet,
+        window_width: f64,
+    ) -> bool {
+        if self.request_style
+            || self.reified_interact_state != interact_state
+            || self.reified_style.is_none()
+        {
+            self.request_style = false;
+            self.reified_interact_state = interact_state;
+
+            let base_style = self
+                .responsive_styles
+                .iter()
+                .filter(|(breakpoint, _)| breakpoint.contains(window_width))
+                .fold(
+                    stylesheet
+                        .style(&self.classes, &interact_state)
+                        .apply(self.style.clone()),
+                    |style, (_, responsive)| style.apply(responsive.clone()),
+                )
+                .reify(&view_style());
+            let new_style = if interact_state.is_hovered {
                 self.hover_style
                     .clone()
                     .unwrap_or_default()
-                    .reify(&base_style),
-            )
-        } else {
-            Some(base_style)
-        };
-
-        self.reified_style.as_ref().unwrap()
+                    .reify(&base_style)
+            } else {
+                base_style
+            };
+            let text_layout_differs = self
+                .reified_style
+                .as_ref()
+                .map(|old_style| old_style.text_layout_differs(&new_style))
+                .unwrap_or(false);
+            self.reified_style = Some(new_style);
+            return text_layout_differs;
+        }
+        false
     }
 }
 
@@ -92,6 +202,15 @@ pub struct AppState {
     pub taffy: taffy::Taffy,
     pub(crate) view_states: HashMap<Id, ViewState>,
     pub(crate) hovered: HashSet<Id>,
+    pub(crate) stylesheet: StyleSheet,
+    /// The draggable view the mouse was just pressed on, and where in it
+    pub(crate) drag_start: Option<(Id, Point)>,
+    pub(crate) drag: Option<DragState>,
+    /// Views painted above all the others at a window position, like tooltips, in the order
+    /// they were shown
+    pub(crate) overlays: Vec<(Id, Point)>,
+    /// Views moved in the window without their layout changing
+    pub(crate) request_compute_layout: bool,
 }
 
 impl Default for AppState {
@@ -112,6 +231,11 @@ impl AppState {
             taffy,
             view_states: HashMap::new(),
             hovered: HashSet::new(),
+            stylesheet: StyleSheet::default(),
+            drag_start: None,
+            drag: None,
+            overlays: Vec::new(),
+            request_compute_layout: false,
         }
     }
 
@@ -130,18 +254,62 @@ impl AppState {
     }
 
     pub fn get_interact_state(&self, id: &Id) -> InteractionState {
-        if self.hovered.contains(id) {
-            return InteractionState { is_hovered: true };
+        InteractionState {
+            is_hovered: self.hovered.contains(id),
+            is_focused: self.focus == Some(*id),
+            is_active: self.active == Some(*id),
         }
-
-        InteractionState::default()
     }
 
     pub fn set_root_size(&mut self, size: Size) {
+        let old_width = self.root_size.width;
         self.root_size = size;
+        // Views with a responsive style that was entered or left need new styles
+        let changed: Vec<Id> = self
+            .view_states
+            .iter()
+            .filter(|(_, view)| {
+                view.responsive_styles.iter().any(|(breakpoint, _)| {
+                    breakpoint.contains(old_width) != breakpoint.contains(size.width)
+                })
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        for id in changed {
+            self.view_state(id).request_style = true;
+            self.request_layout(id);
+        }
         self.compute_layout();
     }
 
+    /// Set the style used while the window width is in `breakpoint`,
+    /// replacing the one previously set for it
+    pub(crate) fn set_responsive_style(&mut self, id: Id, breakpoint: Breakpoint, style: Style) {
+        let view = self.view_state(id);
+        match view
+            .responsive_styles
+            .iter_mut()
+            .find(|(b, _)| *b == breakpoint)
+        {
+            Some((_, existing)) => *existing = style,
+            None => view.responsive_styles.push((breakpoint, style)),
+        }
+        view.request_style = true;
+        self.request_layout(id);
+    }
+
+    /// Paint the view on top of the window with its origin at `origin`, rather than in its parent
+    pub(crate) fn show_overlay(&mut self, id: Id, origin: Point) {
+        match self.overlays.iter_mut().find(|(overlay, _)| *overlay == id) {
+            Some((_, existing)) => *existing = origin,
+            None => self.overlays.push((id, origin)),
+        }
+    }
+
+    pub(crate) fn hide_overlay(&mut self, id: Id) {
+        self.overlays.retain(|(overlay, _)| *overlay != id);
+    }
+
     pub fn compute_layout(&mut self) {
         if let Some(root) = self.root {
             let _ = self.taffy.compute_layout(
@@ -184,16 +352,334 @@ impl AppState {
             .copied()
     }
 
+    /// The size from the origin of a view to the far edges of its children
+    pub(crate) fn content_size(&self, id: Id) -> Size {
+        let children_nodes = match self.view_states.get(&id) {
+            Some(view) => &view.children_nodes,
+            None => return Size::ZERO,
+        };
+        children_nodes
+            .iter()
+            .filter_map(|node| self.taffy.layout(*node).ok())
+            .fold(Size::ZERO, |size, layout| {
+                Size::new(
+                    size.width
+                        .max((layout.location.x + layout.size.width) as f64),
+                    size.height
+                        .max((layout.location.y + layout.size.height) as f64),
+                )
+            })
+    }
+
+    /// Keep the scroll offset of a view with scrollable overflow within its content, returning
+    /// whether it changed
+    pub(crate) fn set_scroll_offset(&mut self, id: Id, offset: Vec2) -> bool {
+        let (overflow_x, overflow_y) = self
+            .view_states
+            .get(&id)
+            .and_then(|view| view.reified_style.as_ref())
+            .map(|style| (style.overflow_x, style.overflow_y))
+            .unwrap_or((Overflow::Visible, Overflow::Visible));
+        if !overflow_x.is_scrollable() && !overflow_y.is_scrollable() {
+            return false;
+        }
+
+        let size = self
+            .get_layout(id)
+            .map(|layout| Size::new(layout.size.width as f64, layout.size.height as f64))
+            .unwrap_or_default();
+        let content_size = self.content_size(id);
+        let clamp = |offset: f64, size: f64, content_size: f64, overflow: Overflow| {
+            if overflow.is_scrollable() {
+                offset.min(content_size - size).max(0.0)
+            } else {
+                0.0
+            }
+        };
+        let offset = Vec2::new(
+            clamp(offset.x, size.width, content_size.width, overflow_x),
+            clamp(offset.y, size.height, content_size.height, overflow_y),
+        );
+        let view = self.view_state(id);
+        if offset == view.scroll_offset {
+            return false;
+        }
+        view.scroll_offset = offset;
+        // Only where the views inside are in the window changes, which doesn't need a new layout
+        self.request_compute_layout = true;
+        true
+    }
+
+    /// The deepest hovered view that can be focused, which a click focuses
+    pub(crate) fn hovered_focusable(&self) -> Option<Id> {
+        IDPATHS.with(|paths| {
+            let paths = paths.borrow();
+            self.hovered
+                .iter()
+                .filter(|id| {
+                    self.view_states
+                        .get(id)
+                        .map(|view| view.tab_index.is_some())
+                        .unwrap_or(false)
+                })
+                .max_by_key(|id| paths.get(id).map(|path| path.0.len()).unwrap_or(0))
+                .copied()
+        })
+    }
+
+    /// Visit the shown views under `node` in tree order, which is the order they are laid out in
+    fn visit_shown(&self, node: Node, ids: &HashMap<Node, Id>, f: &mut impl FnMut(Id)) {
+        if let Some(id) = ids.get(&node) {
+            if self.is_hidden(*id) {
+                return;
+            }
+            f(*id);
+        }
+        // Not every node is a view, like the content node of a scroll view
+        if let Ok(children) = self.taffy.children(node) {
+            for child in children {
+                self.visit_shown(child, ids, f);
+            }
+        }
+    }
+
+    fn node_ids(&self) -> HashMap<Node, Id> {
+        self.view_states
+            .iter()
+            .map(|(id, view)| (view.node, *id))
+            .collect()
+    }
+
+    /// The view that Tab or Shift+Tab moves the focus to. Views with a positive tab index come
+    /// first, then the ones with 0 in tree order, all within the last focus trap that is shown.
+    pub(crate) fn next_tab_focus(&self, backwards: bool) -> Option<Id> {
+        let root = self.root?;
+        let ids = self.node_ids();
+        let mut trap = None;
+        self.visit_shown(root, &ids, &mut |id| {
+            if self.view_states[&id].focus_trap {
+                trap = Some(self.view_states[&id].node);
+            }
+        });
+        let mut focusable = Vec::new();
+        self.visit_shown(
+            trap.unwrap_or(root),
+            &ids,
+            &mut |id| match self.view_states[&id].tab_index {
+                Some(index) if index >= 0 => focusable.push((index, id)),
+                _ => {}
+            },
+        );
+        focusable.sort_by_key(|(index, _)| (*index == 0, *index));
+
+        let len = focusable.len();
+        if len == 0 {
+            return None;
+        }
+        let current = self
+            .focus
+            .and_then(|focus| focusable.iter().position(|(_, id)| *id == focus));
+        let next = match (current, backwards) {
+            (Some(i), false) => (i + 1) % len,
+            (Some(i), true) => (i + len - 1) % len,
+            (None, false) => 0,
+            (None, true) => len - 1,
+        };
+        Some(focusable[next].1)
+    }
+
+    /// The view that an arrow key moves the focus to, within the focus scope around the focused
+    /// view
+    pub(crate) fn next_arrow_focus(&self, backwards: bool) -> Option<Id> {
+        let focus = self.focus?;
+        let mut scope = focus.parent();
+        while let Some(id) = scope {
+            if self
+                .view_states
+                .get(&id)
+                .map(|view| view.focus_scope)
+                .unwrap_or(false)
+            {
+                break;
+            }
+            scope = id.parent();
+        }
+        let scope = scope?;
+
+        let ids = self.node_ids();
+        let mut focusable = Vec::new();
+        self.visit_shown(self.view_states[&scope].node, &ids, &mut |id| {
+            if id != scope && self.view_states[&id].tab_index.is_some() {
+                focusable.push(id);
+            }
+        });
+        let current = focusable.iter().position(|id| *id == focus)?;
+        let next = if backwards {
+            current.checked_sub(1)?
+        } else {
+            current + 1
+        };
+        focusable.get(next).copied()
+    }
+
+    /// The cursor of the deepest hovered view that sets one
+    pub(crate) fn hovered_cursor(&self) -> Option<CursorStyle> {
+        IDPATHS.with(|paths| {
+            let paths = paths.borrow();
+            self.hovered
+                .iter()
+                .filter_map(|id| {
+                    let cursor = self.view_states.get(id)?.reified_style.as_ref()?.cursor?;
+                    let depth = paths.get(id).map(|path| path.0.len()).unwrap_or(0);
+                    Some((depth, cursor))
+                })
+                .max_by_key(|(depth, _)| *depth)
+                .map(|(_, cursor)| cursor)
+        })
+    }
+
+    pub(crate) fn z_index(&self, id: Id) -> i32 {
+        self.view_states
+            .get(&id)
+            .and_then(|view| view.reified_style.as_ref())
+            .map(|style| style.z_index)
+            .unwrap_or(0)
+    }
+
+    /// Sort sibling views into the order they're painted in, by their `z_index` and then their
+    /// order in the tree. Events are sent in the reverse order, so the top view gets them first.
+    pub(crate) fn sort_by_z_index(&self, views: &mut [&mut dyn View]) {
+        views.sort_by_key(|view| self.z_index(view.id()));
+    }
+
     pub(crate) fn update_active(&mut self, id: Id) {
-        self.active = Some(id);
+        if let Some(old) = self.active.replace(id) {
+            self.request_style_update(old);
+        }
+        self.request_style_update(id);
+    }
+
+    pub(crate) fn update_focus(&mut self, id: Id) {
+        if let Some(old) = self.focus.replace(id) {
+            self.request_style_update(old);
+        }
+        self.request_style_update(id);
+    }
+
+    /// Set whether the view is hovered. The view and its children are only laid out again when
+    /// the hover style changes properties that affect layout, otherwise it is just repainted.
+    pub(crate) fn set_hovered(
+        &mut self,
+        id: Id,
+        hovered: bool,
+        view_style: impl FnOnce() -> ReifiedStyle,
+    ) {
+        if hovered {
+            self.hovered.insert(id);
+        } else {
+            self.hovered.remove(&id);
+        }
+        let view = self.view_state(id);
+        let old_style = view.reified_style.clone();
+        let lcx = view.length_context.unwrap_or_default();
+        let new_style = self.reified_style(id, view_style);
+        let layout_differs = old_style
+            .map(|old_style| old_style.layout_differs(new_style, &lcx))
+            .unwrap_or(true);
+        if layout_differs {
+            self.reset_children_layout(id);
+        } else {
+            AppContext::request_paint();
+        }
+    }
+
+    /// The reified style of the view, which is derived again when it was invalidated
+    pub(crate) fn reified_style(
+        &mut self,
+        id: Id,
+        view_style: impl FnOnce() -> ReifiedStyle,
+    ) -> &ReifiedStyle {
+        let interact_state = self.get_interact_state(&id);
+        let view = self
+            .view_states
+            .entry(id)
+            .or_insert_with(|| ViewState::new(&mut self.taffy));
+        if view.fill_reified_style(
+            interact_state,
+            view_style,
+            &self.stylesheet,
+            self.root_size.width,
+        ) {
+            // The views inside inherit the text properties from this one, so the layouts of
+            // their text are out of date. The color is picked up when they are painted.
+            for child in id.all_chilren() {
+                if self.view_states.contains_key(&child) {
+                    self.request_layout(child);
+                }
+            }
+        }
+        self.view_state(id).reified_style.as_ref().unwrap()
+    }
+
+    /// The focus and active states only change the style through stylesheet rules, so only views
+    /// with classes need to be laid out again.
+    pub(crate) fn request_style_update(&mut self, id: Id) {
+        if self
+            .view_states
+            .get(&id)
+            .map(|view| !view.classes.is_empty())
+            .unwrap_or(false)
+        {
+            self.request_layout(id);
+        }
+    }
+
+    pub(crate) fn set_stylesheet(&mut self, stylesheet: StyleSheet) {
+        self.stylesheet = stylesheet;
+        for view in self.view_states.values_mut() {
+            view.request_style = true;
+            view.request_layout = true;
+        }
     }
 }
 
 pub struct EventCx<'a> {
     pub(crate) app_state: &'a mut AppState,
+    pub(crate) propagation_stopped: bool,
+    pub(crate) default_prevented: bool,
 }
 
 impl<'a> EventCx<'a> {
+    pub(crate) fn new(app_state: &'a mut AppState) -> Self {
+        Self {
+            app_state,
+            propagation_stopped: false,
+            default_prevented: false,
+        }
+    }
+
+    /// Stop the event from going to any more views or listeners
+    pub fn stop_propagation(&mut self) {
+        self.propagation_stopped = true;
+    }
+
+    /// Keep views from doing what they normally do with the event, like a click view calling
+    /// its action or the window moving the focus on Tab. The event still propagates.
+    ///
+    /// Views react to an event when they get it, so their default is only prevented by
+    /// listeners that run before that: their own capture listeners and those of their ancestors.
+    pub fn prevent_default(&mut self) {
+        self.default_prevented = true;
+    }
+
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.propagation_stopped
+    }
+
+    pub fn is_default_prevented(&self) -> bool {
+        self.default_prevented
+    }
+
     pub(crate) fn update_active(&mut self, id: Id) {
         self.app_state.update_active(id);
     }
@@ -220,14 +706,86 @@ impl<'a> EventCx<'a> {
             .map(|l| Size::new(l.size.width as f64, l.size.height as f64))
     }
 
-    pub(crate) fn get_event_listener(
-        &self,
-        id: Id,
-    ) -> Option<&HashMap<EventListner, Box<EventCallback>>> {
-        self.app_state
+    /// Run the listener of the view for the event in the phase, returning whether the
+    /// propagation of the event was stopped
+    pub(crate) fn run_listener(&mut self, id: Id, phase: EventPhase, event: &Event) -> bool {
+        let action = self
+            .app_state
             .view_states
             .get(&id)
-            .map(|s| &s.event_listeners)
+            .and_then(|s| s.event_listeners.get(&(event.listener(), phase)))
+            .cloned();
+        if let Some(action) = action {
+            (*action)(self, event);
+        }
+        self.propagation_stopped
+    }
+
+    /// Note a press on a draggable view, find the drop target under the dragged view, or drop
+    /// the payload on it. Returns true when the view is the drop target.
+    pub(crate) fn handle_drag(&mut self, id: Id, event: &Event) -> bool {
+        let app_state = &mut *self.app_state;
+        let view = match app_state.view_states.get(&id) {
+            Some(view) => view,
+            None => return false,
+        };
+        match event {
+            Event::MouseDown(mouse_event) => {
+                // The deepest draggable view gets the drag, and the event bubbles up from it
+                if view.draggable.is_some()
+                    && app_state.drag_start.is_none()
+                    && !self.default_prevented
+                {
+                    app_state.drag_start = Some((id, mouse_event.pos));
+                }
+                false
+            }
+            Event::DragOver(mouse_event) | Event::Drop(mouse_event) => {
+                // Hovered views get mouse events from outside of them
+                let rect = app_state
+                    .get_layout(id)
+                    .map(|layout| Size::new(layout.size.width as f64, layout.size.height as f64))
+                    .unwrap_or_default()
+                    .to_rect();
+                let (drag, target) = match (app_state.drag.as_mut(), view.drop_target.as_ref()) {
+                    (Some(drag), Some(target)) => (drag, target),
+                    _ => return false,
+                };
+                let accepts = drag
+                    .payload
+                    .as_deref()
+                    .map(|payload| (target.accepts)(payload))
+                    .unwrap_or(false);
+                // A drop target inside this view already took it
+                if drag.over.is_some() && matches!(event, Event::DragOver(_)) {
+                    return false;
+                }
+                if !accepts || !rect.contains(mouse_event.pos) {
+                    return false;
+                }
+                if let Event::Drop(_) = event {
+                    if let Some(payload) = drag.payload.take() {
+                        (target.on_drop)(payload, mouse_event.pos);
+                    }
+                } else {
+                    drag.over = Some(id);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Run the listeners of the view for an event that only goes to that view, like
+    /// [`Event::PointerEnter`], without changing the propagation of the event being dispatched
+    pub(crate) fn run_own_listeners(&mut self, id: Id, event: &Event) {
+        let propagation = (self.propagation_stopped, self.default_prevented);
+        self.propagation_stopped = false;
+        self.run_listener(id, EventPhase::Capture, event);
+        if !self.propagation_stopped {
+            self.run_listener(id, EventPhase::Bubble, event);
+        }
+        (self.propagation_stopped, self.default_prevented) = propagation;
     }
 
     pub(crate) fn offset_event(&self, id: Id, event: Event) -> Event {
@@ -248,6 +806,9 @@ impl<'a> EventCx<'a> {
     }
 
     pub(crate) fn should_send(&mut self, id: Id, event: &Event) -> bool {
+        if let Event::WindowMouseLeave = event {
+            return self.app_state.hovered.contains(&id);
+        }
         let point = event.point();
         if let Some(point) = point {
             if self.app_state.is_hidden(id) {
@@ -271,10 +832,12 @@ impl<'a> EventCx<'a> {
     }
 }
 
-#[derive(Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct InteractionState {
-    is_hovered: bool,
-    // TODO: Add focus, active, disabled and consider changing to a bitflag
+    pub(crate) is_hovered: bool,
+    pub(crate) is_focused: bool,
+    pub(crate) is_active: bool,
+    // TODO: Add disabled and consider changing to a bitflag
 }
 
 pub struct LayoutCx<'a> {
@@ -284,16 +847,56 @@ pub struct LayoutCx<'a> {
     pub(crate) font_family: Option<String>,
     pub(crate) font_weight: Option<Weight>,
     pub(crate) font_style: Option<FontStyle>,
+    pub(crate) line_height: Option<f32>,
+    pub(crate) letter_spacing: Option<f32>,
+    pub(crate) text_align: Option<TextAlign>,
+    pub(crate) white_space: Option<WhiteSpace>,
+    pub(crate) text_overflow: Option<TextOverflow>,
+    pub(crate) root_font_size: Option<f32>,
     pub(crate) window_origin: Point,
     pub(crate) saved_viewports: Vec<Option<Rect>>,
     pub(crate) saved_font_sizes: Vec<Option<f32>>,
     pub(crate) saved_font_families: Vec<Option<String>>,
     pub(crate) saved_font_weights: Vec<Option<Weight>>,
     pub(crate) saved_font_styles: Vec<Option<FontStyle>>,
+    pub(crate) saved_line_heights: Vec<Option<f32>>,
+    pub(crate) saved_letter_spacings: Vec<Option<f32>>,
+    pub(crate) saved_text_aligns: Vec<Option<TextAlign>>,
+    pub(crate) saved_white_spaces: Vec<Option<WhiteSpace>>,
+    pub(crate) saved_text_overflows: Vec<Option<TextOverflow>>,
     pub(crate) saved_window_origins: Vec<Point>,
 }
 
 impl<'a> LayoutCx<'a> {
+    pub(crate) fn new(app_state: &'a mut AppState) -> Self {
+        Self {
+            app_state,
+            viewport: None,
+            font_size: None,
+            font_family: None,
+            font_weight: None,
+            font_style: None,
+            line_height: None,
+            letter_spacing: None,
+            text_align: None,
+            white_space: None,
+            text_overflow: None,
+            root_font_size: None,
+            window_origin: Point::ZERO,
+            saved_viewports: Vec::new(),
+            saved_font_sizes: Vec::new(),
+            saved_font_families: Vec::new(),
+            saved_font_weights: Vec::new(),
+            saved_font_styles: Vec::new(),
+            saved_line_heights: Vec::new(),
+            saved_letter_spacings: Vec::new(),
+            saved_text_aligns: Vec::new(),
+            saved_white_spaces: Vec::new(),
+            saved_text_overflows: Vec::new(),
+            saved_window_origins: Vec::new(),
+        }
+    }
+
     pub(crate) fn clear(&mut self) {
         self.viewport = None;
         self.font_size = None;
@@ -303,6 +906,11 @@ impl<'a> LayoutCx<'a> {
         self.saved_font_families.clear();
         self.saved_font_weights.clear();
         self.saved_font_styles.clear();
+        self.saved_line_heights.clear();
+        self.saved_letter_spacings.clear();
+        self.saved_text_aligns.clear();
+        self.saved_white_spaces.clear();
+        self.saved_text_overflows.clear();
         self.saved_window_origins.clear();
     }
 
@@ -312,6 +920,11 @@ impl<'a> LayoutCx<'a> {
         self.saved_font_families.push(self.font_family.clone());
         self.saved_font_weights.push(self.font_weight);
         self.saved_font_styles.push(self.font_style);
+        self.saved_line_heights.push(self.line_height);
+        self.saved_letter_spacings.push(self.letter_spacing);
+        self.saved_text_aligns.push(self.text_align);
+        self.saved_white_spaces.push(self.white_space);
+        self.saved_text_overflows.push(self.text_overflow);
         self.saved_window_origins.push(self.window_origin);
     }
 
@@ -321,6 +934,11 @@ impl<'a> LayoutCx<'a> {
         self.font_family = self.saved_font_families.pop().unwrap_or_default();
         self.font_weight = self.saved_font_weights.pop().unwrap_or_default();
         self.font_style = self.saved_font_styles.pop().unwrap_or_default();
+        self.line_height = self.saved_line_heights.pop().unwrap_or_default();
+        self.letter_spacing = self.saved_letter_spacings.pop().unwrap_or_default();
+        self.text_align = self.saved_text_aligns.pop().unwrap_or_default();
+        self.white_space = self.saved_white_spaces.pop().unwrap_or_default();
+        self.text_overflow = self.saved_text_overflows.pop().unwrap_or_default();
         self.window_origin = self.saved_window_origins.pop().unwrap_or_default();
     }
 
@@ -332,14 +950,23 @@ impl<'a> LayoutCx<'a> {
         self.font_family.as_deref()
     }
 
+    /// What relative lengths in the current view's style resolve against
+    pub fn length_context(&self) -> LengthContext {
+        LengthContext {
+            font_size: self.font_size.unwrap_or(DEFAULT_FONT_SIZE),
+            root_font_size: self.root_font_size.unwrap_or(DEFAULT_FONT_SIZE),
+            viewport: self.app_state.root_size,
+        }
+    }
+
+    /// The reified style of the view, where `view_style` gives the defaults of the view if the
+    /// cached style has to be derived again
     pub fn get_reified_style(
         &mut self,
-        view_style: &ReifiedStyle,
+        view_style: impl FnOnce() -> ReifiedStyle,
         id: Id,
     ) -> Option<&ReifiedStyle> {
-        let intr_state = self.app_state.get_interact_state(&id);
-        let view_state = self.app_state.view_state(id);
-        Some(view_state.fill_reified_style(intr_state, view_style))
+        Some(self.app_state.reified_style(id, view_style))
     }
 
     pub fn get_layout(&self, id: Id) -> Option<Layout> {
@@ -367,6 +994,7 @@ impl<'a> LayoutCx<'a> {
         has_children: bool,
         mut children: impl FnMut(&mut LayoutCx) -> Vec<Node>,
     ) -> Node {
+        let length_context = self.length_context();
         let view = self.app_state.view_state(id);
         let node = view.node;
         if !view.request_layout {
@@ -374,7 +1002,11 @@ impl<'a> LayoutCx<'a> {
         }
         view.request_layout = false;
         // TODO: should we assume that the reified style is already initialized?
-        let style = view.reified_style.as_ref().unwrap().to_taffy_style();
+        let style = view
+            .reified_style
+            .as_ref()
+            .unwrap()
+            .to_taffy_style(&length_context);
         let _ = self.app_state.taffy.set_style(node, style);
 
         if has_children {
@@ -405,6 +1037,11 @@ pub struct PaintCx<'a> {
     pub(crate) font_family: Option<String>,
     pub(crate) font_weight: Option<Weight>,
     pub(crate) font_style: Option<FontStyle>,
+    pub(crate) line_height: Option<f32>,
+    pub(crate) letter_spacing: Option<f32>,
+    pub(crate) text_align: Option<TextAlign>,
+    pub(crate) white_space: Option<WhiteSpace>,
+    pub(crate) text_overflow: Option<TextOverflow>,
     pub(crate) saved_transforms: Vec<Affine>,
     pub(crate) saved_clips: Vec<Option<Rect>>,
     pub(crate) saved_colors: Vec<Option<Color>>,
@@ -412,6 +1049,11 @@ pub struct PaintCx<'a> {
     pub(crate) saved_font_families: Vec<Option<String>>,
     pub(crate) saved_font_weights: Vec<Option<Weight>>,
     pub(crate) saved_font_styles: Vec<Option<FontStyle>>,
+    pub(crate) saved_line_heights: Vec<Option<f32>>,
+    pub(crate) saved_letter_spacings: Vec<Option<f32>>,
+    pub(crate) saved_text_aligns: Vec<Option<TextAlign>>,
+    pub(crate) saved_white_spaces: Vec<Option<WhiteSpace>>,
+    pub(crate) saved_text_overflows: Vec<Option<TextOverflow>>,
 }
 
 impl<'a> PaintCx<'a> {
@@ -423,6 +1065,11 @@ impl<'a> PaintCx<'a> {
         self.saved_font_families.push(self.font_family.clone());
         self.saved_font_weights.push(self.font_weight);
         self.saved_font_styles.push(self.font_style);
+        self.saved_line_heights.push(self.line_height);
+        self.saved_letter_spacings.push(self.letter_spacing);
+        self.saved_text_aligns.push(self.text_align);
+        self.saved_white_spaces.push(self.white_space);
+        self.saved_text_overflows.push(self.text_overflow);
     }
 
     pub fn restore(&mut self) {
@@ -433,6 +1080,11 @@ impl<'a> PaintCx<'a> {
         self.font_family = self.saved_font_families.pop().unwrap_or_default();
         self.font_weight = self.saved_font_weights.pop().unwrap_or_default();
         self.font_style = self.saved_font_styles.pop().unwrap_or_default();
+        self.line_height = self.saved_line_heights.pop().unwrap_or_default();
+        self.letter_spacing = self.saved_letter_spacings.pop().unwrap_or_default();
+        self.text_align = self.saved_text_aligns.pop().unwrap_or_default();
+        self.white_space = self.saved_white_spaces.pop().unwrap_or_default();
+        self.text_overflow = self.saved_text_overflows.pop().unwrap_or_default();
         let renderer = self.paint_state.renderer.as_mut().unwrap();
         renderer.transform(self.transform);
         if let Some(rect) = self.clip {
@@ -462,16 +1114,47 @@ impl<'a> PaintCx<'a> {
         self.app_state.get_layout(id)
     }
 
+    /// The reified style of the view, where `view_style` gives the defaults of the view if the
+    /// cached style has to be derived again
     pub fn get_reified_style(
         &mut self,
-        view_style: &ReifiedStyle,
+        view_style: impl FnOnce() -> ReifiedStyle,
         id: Id,
     ) -> Option<&ReifiedStyle> {
-        let intr_state = self.app_state.get_interact_state(&id);
-        self.app_state
+        if !self.app_state.view_states.contains_key(&id) {
+            return None;
+        }
+        Some(self.app_state.reified_style(id, view_style))
+    }
+
+    /// The border widths of the view, as resolved to points during layout
+    pub(crate) fn get_border(&self, id: Id) -> taffy::prelude::Rect<f32> {
+        let border = self
+            .app_state
             .view_states
-            .get_mut(&id)
-            .map(|s| s.fill_reified_style(intr_state, view_style))
+            .get(&id)
+            .and_then(|view| self.app_state.taffy.style(view.node).ok())
+            .map(|style| style.border);
+        let width = |length: Option<LengthPercentage>| match length {
+            Some(LengthPercentage::Points(width)) => width,
+            _ => 0.0,
+        };
+        taffy::prelude::Rect {
+            left: width(border.map(|b| b.left)),
+            top: width(border.map(|b| b.top)),
+            right: width(border.map(|b| b.right)),
+            bottom: width(border.map(|b| b.bottom)),
+        }
+    }
+
+    pub(crate) fn set_clip(&mut self, clip: Option<Rect>) {
+        self.clip = clip;
+        let renderer = self.paint_state.renderer.as_mut().unwrap();
+        if let Some(rect) = clip {
+            renderer.clip(&rect);
+        } else {
+            renderer.clear_clip();
+        }
     }
 
     pub fn clip(&mut self, shape: &impl Shape) {
@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use glazier::{
     kurbo::{Point, Size},
     KeyEvent, MouseEvent,
@@ -6,9 +8,37 @@ use glazier::{
 #[derive(Hash, PartialEq, Eq)]
 pub enum EventListner {
     KeyDown,
+    KeyUp,
+    CompositionStart,
+    CompositionUpdate,
+    CompositionCommit,
+    MouseDown,
+    MouseUp,
+    MouseMove,
     MouseWheel,
+    PointerEnter,
+    PointerLeave,
+    FocusGained,
+    FocusLost,
+    DragEnter,
+    DragLeave,
+    DragOver,
+    Drop,
     WindowClosed,
     WindowResized,
+    WindowGotFocus,
+    WindowLostFocus,
+    WindowMouseLeave,
+}
+
+/// When an event listener runs. An event is sent down the tree from the root to the view it is
+/// for, which is the capture phase, and then back up to the root, which is the bubble phase. So
+/// capture listeners of a view run before the views inside it get the event, and bubble listeners
+/// after they did.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum EventPhase {
+    Capture,
+    Bubble,
 }
 
 #[derive(Debug, Clone)]
@@ -17,9 +47,47 @@ pub enum Event {
     MouseUp(MouseEvent),
     MouseMove(MouseEvent),
     MouseWheel(MouseEvent),
+    /// Sent to a view when the mouse moves onto it
+    PointerEnter,
+    /// Sent to a view when the mouse moves off it, or out of the window while it was hovered
+    PointerLeave,
     KeyDown(KeyEvent),
+    KeyUp(KeyEvent),
+    /// The input method started composing text in place of the selection of the focused text
+    /// input
+    CompositionStart,
+    /// The text being composed changed. It isn't part of the text of the view until it is
+    /// committed. `cursor` is the selection within the composed text.
+    CompositionUpdate {
+        text: String,
+        cursor: Option<Range<usize>>,
+    },
+    /// Replace `range` of the text of the focused text input with `text`, which ends the
+    /// composition when there is one. Typed text comes this way too when no view used its key
+    /// event.
+    CompositionCommit {
+        text: String,
+        range: Range<usize>,
+    },
+    /// Sent to a view when it gets the keyboard focus
+    FocusGained,
+    /// Sent to a view when the keyboard focus moves away from it
+    FocusLost,
+    /// Sent to a drop target when a dragged view that it accepts moves onto it
+    DragEnter,
+    /// Sent to a drop target when the dragged view moves off it or is dropped
+    DragLeave,
+    /// The mouse moved while dragging a view
+    DragOver(MouseEvent),
+    /// The mouse was released while dragging a view
+    Drop(MouseEvent),
     WindowClosed,
     WindowResized(Size),
+    WindowGotFocus,
+    WindowLostFocus,
+    /// The mouse left the window. It is also sent to the hovered views, so they get their
+    /// [`Event::PointerLeave`].
+    WindowMouseLeave,
 }
 
 impl Event {
@@ -29,9 +97,24 @@ impl Event {
             | Event::MouseUp(_)
             | Event::MouseMove(_)
             | Event::MouseWheel(_)
+            | Event::PointerEnter
+            | Event::PointerLeave
+            | Event::FocusGained
+            | Event::FocusLost
+            | Event::DragEnter
+            | Event::DragLeave
+            | Event::DragOver(_)
+            | Event::Drop(_)
             | Event::WindowClosed
-            | Event::WindowResized(_) => false,
-            Event::KeyDown(_) => true,
+            | Event::WindowResized(_)
+            | Event::WindowGotFocus
+            | Event::WindowLostFocus
+            | Event::WindowMouseLeave => false,
+            Event::KeyDown(_)
+            | Event::KeyUp(_)
+            | Event::CompositionStart
+            | Event::CompositionUpdate { .. }
+            | Event::CompositionCommit { .. } => true,
         }
     }
 
@@ -41,7 +124,24 @@ impl Event {
             | Event::MouseUp(_)
             | Event::MouseMove(_)
             | Event::MouseWheel(_) => true,
-            Event::KeyDown(_) | Event::WindowClosed | Event::WindowResized(_) => false,
+            Event::PointerEnter
+            | Event::PointerLeave
+            | Event::KeyDown(_)
+            | Event::KeyUp(_)
+            | Event::CompositionStart
+            | Event::CompositionUpdate { .. }
+            | Event::CompositionCommit { .. }
+            | Event::FocusGained
+            | Event::FocusLost
+            | Event::DragEnter
+            | Event::DragLeave
+            | Event::DragOver(_)
+            | Event::Drop(_)
+            | Event::WindowClosed
+            | Event::WindowResized(_)
+            | Event::WindowGotFocus
+            | Event::WindowLostFocus
+            | Event::WindowMouseLeave => false,
         }
     }
 
@@ -50,8 +150,25 @@ impl Event {
             Event::MouseDown(mouse_event)
             | Event::MouseUp(mouse_event)
             | Event::MouseMove(mouse_event)
-            | Event::MouseWheel(mouse_event) => Some(mouse_event.pos),
-            Event::KeyDown(_) | Event::WindowClosed | Event::WindowResized(_) => None,
+            | Event::MouseWheel(mouse_event)
+            | Event::DragOver(mouse_event)
+            | Event::Drop(mouse_event) => Some(mouse_event.pos),
+            Event::PointerEnter
+            | Event::PointerLeave
+            | Event::KeyDown(_)
+            | Event::KeyUp(_)
+            | Event::CompositionStart
+            | Event::CompositionUpdate { .. }
+            | Event::CompositionCommit { .. }
+            | Event::FocusGained
+            | Event::FocusLost
+            | Event::DragEnter
+            | Event::DragLeave
+            | Event::WindowClosed
+            | Event::WindowResized(_)
+            | Event::WindowGotFocus
+            | Event::WindowLostFocus
+            | Event::WindowMouseLeave => None,
         }
     }
 
@@ -60,23 +177,55 @@ impl Event {
             Event::MouseDown(mouse_event)
             | Event::MouseUp(mouse_event)
             | Event::MouseMove(mouse_event)
-            | Event::MouseWheel(mouse_event) => {
+            | Event::MouseWheel(mouse_event)
+            | Event::DragOver(mouse_event)
+            | Event::Drop(mouse_event) => {
                 mouse_event.pos -= offset;
             }
-            Event::KeyDown(_) | Event::WindowClosed | Event::WindowResized(_) => {}
+            Event::PointerEnter
+            | Event::PointerLeave
+            | Event::KeyDown(_)
+            | Event::KeyUp(_)
+            | Event::CompositionStart
+            | Event::CompositionUpdate { .. }
+            | Event::CompositionCommit { .. }
+            | Event::FocusGained
+            | Event::FocusLost
+            | Event::DragEnter
+            | Event::DragLeave
+            | Event::WindowClosed
+            | Event::WindowResized(_)
+            | Event::WindowGotFocus
+            | Event::WindowLostFocus
+            | Event::WindowMouseLeave => {}
         }
         self
     }
 
-    pub fn listener(&self) -> Option<EventListner> {
+    pub fn listener(&self) -> EventListner {
         match self {
-            Event::MouseDown(_) => None,
-            Event::MouseUp(_) => None,
-            Event::MouseMove(_) => None,
-            Event::MouseWheel(_) => Some(EventListner::MouseWheel),
-            Event::KeyDown(_) => Some(EventListner::KeyDown),
-            Event::WindowClosed => Some(EventListner::WindowClosed),
-            Event::WindowResized(_) => Some(EventListner::WindowResized),
+            Event::MouseDown(_) => EventListner::MouseDown,
+            Event::MouseUp(_) => EventListner::MouseUp,
+            Event::MouseMove(_) => EventListner::MouseMove,
+            Event::MouseWheel(_) => EventListner::MouseWheel,
+            Event::PointerEnter => EventListner::PointerEnter,
+            Event::PointerLeave => EventListner::PointerLeave,
+            Event::KeyDown(_) => EventListner::KeyDown,
+            Event::KeyUp(_) => EventListner::KeyUp,
+            Event::CompositionStart => EventListner::CompositionStart,
+            Event::CompositionUpdate { .. } => EventListner::CompositionUpdate,
+            Event::CompositionCommit { .. } => EventListner::CompositionCommit,
+            Event::FocusGained => EventListner::FocusGained,
+            Event::FocusLost => EventListner::FocusLost,
+            Event::DragEnter => EventListner::DragEnter,
+            Event::DragLeave => EventListner::DragLeave,
+            Event::DragOver(_) => EventListner::DragOver,
+            Event::Drop(_) => EventListner::Drop,
+            Event::WindowClosed => EventListner::WindowClosed,
+            Event::WindowResized(_) => EventListner::WindowResized,
+            Event::WindowGotFocus => EventListner::WindowGotFocus,
+            Event::WindowLostFocus => EventListner::WindowLostFocus,
+            Event::WindowMouseLeave => EventListner::WindowMouseLeave,
         }
     }
 }
@@ -0,0 +1,376 @@
+//! # Gestures
+//! Recognizers that turn the raw mouse events a view gets into gestures: clicks of the primary
+//! and secondary buttons, long presses, drags that start after the mouse moved a few pixels, and
+//! flings at the end of a fast drag. They are added to views with the gesture methods of
+//! [`Decorators`](crate::views::Decorators).
+//!
+//! A view with gestures becomes the active view when pressed, so it keeps getting the mouse
+//! events until the button is released, unless a view inside it already took the press.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use glazier::{
+    kurbo::{Point, Rect, Vec2},
+    MouseButton, MouseEvent,
+};
+
+use crate::{app::AppContext, context::EventCx, event::Event, id::Id};
+
+/// How far the mouse has to move while pressed before a drag starts, for drag gestures and for
+/// dragging draggable views
+pub const DRAG_THRESHOLD: f64 = 4.0;
+/// How long a press has to be held to be a long press, unless another duration is given
+pub const LONG_PRESS_DURATION: Duration = Duration::from_millis(500);
+/// The slowest release of a drag that still flings, in pixels per second
+const MIN_FLING_SPEED: f64 = 300.0;
+/// How far back mouse positions are used to measure the speed of a drag
+const VELOCITY_WINDOW: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DragPhase {
+    Start,
+    Update,
+    End,
+}
+
+/// A drag of the mouse over a view, in the coordinates of the view
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DragGesture {
+    pub phase: DragPhase,
+    /// Where the mouse was pressed
+    pub start: Point,
+    pub pos: Point,
+    /// How far the mouse moved since the last update of the drag
+    pub delta: Vec2,
+}
+
+impl DragGesture {
+    /// How far the mouse moved since it was pressed
+    pub fn offset(&self) -> Vec2 {
+        self.pos - self.start
+    }
+}
+
+pub type PointCallback = dyn Fn(Point);
+pub type DragCallback = dyn Fn(&DragGesture);
+/// Gets the velocity of the mouse in pixels per second
+pub type FlingCallback = dyn Fn(Vec2);
+
+pub enum GestureHandler {
+    SecondaryClick(Box<PointCallback>),
+    LongPress(Duration, Box<PointCallback>),
+    Drag(Box<DragCallback>),
+    Fling(Box<FlingCallback>),
+}
+
+/// A press of the primary button that gestures are being recognized in
+struct Press {
+    start: Point,
+    time: Instant,
+    pos: Point,
+    dragging: bool,
+    long_pressed: bool,
+    /// The recent positions of the mouse, to measure its velocity
+    samples: VecDeque<(Instant, Point)>,
+}
+
+/// The gesture handlers of a view, and the state of the gesture in progress
+#[derive(Default)]
+pub(crate) struct Gestures {
+    on_secondary_click: Option<Box<PointCallback>>,
+    on_long_press: Option<(Duration, Box<PointCallback>)>,
+    on_drag: Option<Box<DragCallback>>,
+    on_fling: Option<Box<FlingCallback>>,
+    press: Option<Press>,
+    secondary_pressed: bool,
+}
+
+impl Gestures {
+    pub(crate) fn set_handler(&mut self, handler: GestureHandler) {
+        match handler {
+            GestureHandler::SecondaryClick(action) => self.on_secondary_click = Some(action),
+            GestureHandler::LongPress(duration, action) => {
+                self.on_long_press = Some((duration, action))
+            }
+            GestureHandler::Drag(action) => self.on_drag = Some(action),
+            GestureHandler::Fling(action) => self.on_fling = Some(action),
+        }
+    }
+
+    fn tracks_presses(&self) -> bool {
+        self.on_long_press.is_some() || self.on_drag.is_some() || self.on_fling.is_some()
+    }
+
+    /// Feed a mouse event in the coordinates of the view, whose size is `rect`. Returns whether
+    /// the event started a press, which the view should capture the mouse for.
+    pub(crate) fn event(&mut self, event: &Event, rect: Rect, now: Instant) -> bool {
+        match event {
+            Event::MouseDown(mouse_event) => self.mouse_down(mouse_event, now),
+            Event::MouseMove(mouse_event) => {
+                self.mouse_move(mouse_event, now);
+                false
+            }
+            Event::MouseUp(mouse_event) => {
+                self.mouse_up(mouse_event, rect, now);
+                false
+            }
+            _ => false,
+        }
+    }
+
+    fn mouse_down(&mut self, event: &MouseEvent, now: Instant) -> bool {
+        match event.button {
+            MouseButton::Left if self.tracks_presses() => {
+                self.press = Some(Press {
+                    start: event.pos,
+                    time: now,
+                    pos: event.pos,
+                    dragging: false,
+                    long_pressed: false,
+                    samples: VecDeque::from([(now, event.pos)]),
+                });
+                true
+            }
+            MouseButton::Right if self.on_secondary_click.is_some() => {
+                self.secondary_pressed = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn mouse_move(&mut self, event: &MouseEvent, now: Instant) {
+        let press = match self.press.as_mut() {
+            Some(press) => press,
+            None => return,
+        };
+        let delta = event.pos - press.pos;
+        press.pos = event.pos;
+        press.samples.push_back((now, event.pos));
+        while press.samples.len() > 2 && now.duration_since(press.samples[0].0) > VELOCITY_WINDOW {
+            press.samples.pop_front();
+        }
+
+        let started = !press.dragging && (event.pos - press.start).hypot() >= DRAG_THRESHOLD;
+        press.dragging |= started;
+        if let Some(on_drag) = self.on_drag.as_ref() {
+            if started {
+                on_drag(&DragGesture {
+                    phase: DragPhase::Start,
+                    start: press.start,
+                    pos: event.pos,
+                    delta: event.pos - press.start,
+                });
+            } else if press.dragging {
+                on_drag(&DragGesture {
+                    phase: DragPhase::Update,
+                    start: press.start,
+                    pos: event.pos,
+                    delta,
+                });
+            }
+        }
+        self.check_long_press(now);
+    }
+
+    fn mouse_up(&mut self, event: &MouseEvent, rect: Rect, now: Instant) {
+        match event.button {
+            MouseButton::Left => {
+                self.check_long_press(now);
+                let press = match self.press.take() {
+                    Some(press) => press,
+                    None => return,
+                };
+                if !press.dragging {
+                    return;
+                }
+                if let Some(on_drag) = self.on_drag.as_ref() {
+                    on_drag(&DragGesture {
+                        phase: DragPhase::End,
+                        start: press.start,
+                        pos: event.pos,
+                        delta: event.pos - press.pos,
+                    });
+                }
+                if let Some(on_fling) = self.on_fling.as_ref() {
+                    let velocity = press
+                        .samples
+                        .iter()
+                        .find(|(time, _)| now.duration_since(*time) <= VELOCITY_WINDOW)
+                        .filter(|(time, _)| now > *time)
+                        .map(|(time, pos)| {
+                            (event.pos - *pos) / now.duration_since(*time).as_secs_f64()
+                        });
+                    if let Some(velocity) = velocity.filter(|v| v.hypot() >= MIN_FLING_SPEED) {
+                        on_fling(velocity);
+                    }
+                }
+            }
+            MouseButton::Right => {
+                let pressed = std::mem::take(&mut self.secondary_pressed);
+                if let Some(on_secondary_click) = self.on_secondary_click.as_ref() {
+                    if pressed && rect.contains(event.pos) {
+                        on_secondary_click(event.pos);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// How long a press has to be held to be a long press, if the view has a long press handler
+    fn long_press_duration(&self) -> Option<Duration> {
+        self.on_long_press.as_ref().map(|(duration, _)| *duration)
+    }
+
+    /// A press that was held long enough without moving away is a long press. This is checked
+    /// when the timer started by the press fires, and when the mouse moves or is released.
+    pub(crate) fn check_long_press(&mut self, now: Instant) {
+        let (press, (duration, on_long_press)) = match (self.press.as_mut(), &self.on_long_press) {
+            (Some(press), Some(long_press)) => (press, long_press),
+            _ => return,
+        };
+        if !press.dragging
+            && !press.long_pressed
+            && (press.pos - press.start).hypot() < DRAG_THRESHOLD
+            && now.duration_since(press.time) >= *duration
+        {
+            press.long_pressed = true;
+            on_long_press(press.start);
+        }
+    }
+}
+
+/// Recognize the gestures of the view in the event. Returns true when the view took the press.
+pub(crate) fn gesture_event(cx: &mut EventCx, id: Id, event: &Event) -> bool {
+    if cx.default_prevented {
+        return false;
+    }
+    // A view inside this one that already took the press keeps it
+    if let Event::MouseDown(_) = event {
+        if cx
+            .app_state
+            .active
+            .map(|active| active != id)
+            .unwrap_or(false)
+        {
+            return false;
+        }
+    }
+    let rect = cx.get_size(id).unwrap_or_default().to_rect();
+    let gestures = match cx.app_state.view_states.get_mut(&id) {
+        Some(view) => &mut view.gestures,
+        None => return false,
+    };
+    let captured = gestures.event(event, rect, Instant::now());
+    if captured {
+        if let (Event::MouseDown(_), Some(duration)) = (event, gestures.long_press_duration()) {
+            AppContext::schedule_timer(
+                duration,
+                Box::new(move || AppContext::update_long_press_timer(id)),
+            );
+        }
+        cx.update_active(id);
+    }
+    captured
+}
+
+/// A press of the primary button on a view that acts on clicks makes it the active view, and a
+/// click is releasing the button over the view. `count` is which click of a multi-click the view
+/// acts on. Returns whether the view used the event and whether it completed a click.
+pub(crate) fn click_event(cx: &mut EventCx, id: Id, event: &Event, count: u8) -> (bool, bool) {
+    match event {
+        Event::MouseDown(event) if event.button == MouseButton::Left && event.count == count => {
+            cx.update_active(id);
+            (true, false)
+        }
+        Event::MouseUp(event)
+            if event.button == MouseButton::Left && cx.app_state.active == Some(id) =>
+        {
+            let rect = cx.get_size(id).unwrap_or_default().to_rect();
+            (true, rect.contains(event.pos))
+        }
+        _ => (false, false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::RefCell,
+        rc::Rc,
+        time::{Duration, Instant},
+    };
+
+    use glazier::{
+        kurbo::{Point, Size, Vec2},
+        Modifiers, MouseButton, MouseButtons, MouseEvent,
+    };
+
+    use super::{DragPhase, GestureHandler, Gestures};
+    use crate::event::Event;
+
+    fn mouse(button: MouseButton, x: f64, y: f64) -> MouseEvent {
+        MouseEvent {
+            pos: Point::new(x, y),
+            buttons: MouseButtons::new(),
+            mods: Modifiers::empty(),
+            count: 1,
+            focus: false,
+            button,
+            wheel_delta: Vec2::ZERO,
+        }
+    }
+
+    #[test]
+    fn drag_fling_and_secondary_click() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut gestures = Gestures::default();
+        let drag_log = log.clone();
+        gestures.set_handler(GestureHandler::Drag(Box::new(move |drag| {
+            drag_log
+                .borrow_mut()
+                .push(format!("{:?} {:?}", drag.phase, drag.delta))
+        })));
+        let fling_log = log.clone();
+        gestures.set_handler(GestureHandler::Fling(Box::new(move |_| {
+            fling_log.borrow_mut().push("fling".to_string())
+        })));
+        let click_log = log.clone();
+        gestures.set_handler(GestureHandler::SecondaryClick(Box::new(move |_| {
+            click_log.borrow_mut().push("secondary".to_string())
+        })));
+
+        let rect = Size::new(100.0, 100.0).to_rect();
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let left = MouseButton::Left;
+        assert!(gestures.event(&Event::MouseDown(mouse(left, 10.0, 10.0)), rect, at(0)));
+        // Below the threshold
+        gestures.event(&Event::MouseMove(mouse(left, 12.0, 10.0)), rect, at(10));
+        gestures.event(&Event::MouseMove(mouse(left, 20.0, 10.0)), rect, at(20));
+        gestures.event(&Event::MouseMove(mouse(left, 30.0, 10.0)), rect, at(30));
+        gestures.event(&Event::MouseUp(mouse(left, 40.0, 10.0)), rect, at(40));
+        assert_eq!(
+            *log.borrow(),
+            [
+                format!("{:?} {:?}", DragPhase::Start, Vec2::new(10.0, 0.0)),
+                format!("{:?} {:?}", DragPhase::Update, Vec2::new(10.0, 0.0)),
+                format!("{:?} {:?}", DragPhase::End, Vec2::new(10.0, 0.0)),
+                "fling".to_string(),
+            ]
+        );
+
+        log.borrow_mut().clear();
+        let right = MouseButton::Right;
+        assert!(gestures.event(&Event::MouseDown(mouse(right, 10.0, 10.0)), rect, at(50)));
+        gestures.event(&Event::MouseUp(mouse(right, 10.0, 10.0)), rect, at(60));
+        // Released outside of the view
+        gestures.event(&Event::MouseDown(mouse(right, 10.0, 10.0)), rect, at(70));
+        gestures.event(&Event::MouseUp(mouse(right, 200.0, 10.0)), rect, at(80));
+        assert_eq!(*log.borrow(), ["secondary".to_string()]);
+    }
+}
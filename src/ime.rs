@@ -0,0 +1,326 @@
+//! # Input methods
+//! A view takes text from the input method of the platform by giving its text, selection and
+//! caret with [`Decorators::text_input`](crate::views::Decorators::text_input). While it has the
+//! keyboard focus, the key events that no view used go to the input method, and the text that is
+//! typed or composed with it, like Chinese and Japanese, comes to the view as
+//! [`Event::CompositionStart`], [`Event::CompositionUpdate`] and [`Event::CompositionCommit`].
+
+use std::{borrow::Cow, cell::RefCell, ops::Range, rc::Rc};
+
+use glazier::{
+    kurbo::{Point, Rect},
+    text::{Action, Affinity, HitTestPoint, InputHandler, Selection},
+};
+
+use crate::{event::Event, id::Id};
+
+/// What the input method is told about a text input
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextInputState {
+    pub text: String,
+    /// The selected byte range of the text, which is empty where the caret is
+    pub selection: Range<usize>,
+    /// The area of the caret in the view, which the input method puts its windows next to
+    pub caret: Rect,
+}
+
+pub type TextInputFn = dyn Fn() -> TextInputState;
+
+/// The text of the focused text input as the input method sees it. The input method edits it
+/// directly, and the views are told about the edits once it is done.
+pub(crate) struct ImeSession {
+    pub(crate) id: Id,
+    /// The text and selection of the view
+    view_text: String,
+    view_selection: Range<usize>,
+    /// The text with the composed text in it
+    text: String,
+    selection: Selection,
+    composition: Option<Range<usize>>,
+    /// The view was told that text is being composed
+    composing: bool,
+    /// The input method changed something since the view was last told
+    edited: bool,
+    /// How far from the start and the end of the text the edits since the last commit are
+    edit_start: usize,
+    edit_end: usize,
+    /// Where the view and its caret are in the window
+    rect: Rect,
+    caret: Rect,
+}
+
+impl ImeSession {
+    pub(crate) fn new(id: Id, state: TextInputState, rect: Rect) -> Self {
+        let mut session = Self {
+            id,
+            view_text: String::new(),
+            view_selection: 0..0,
+            text: String::new(),
+            selection: Selection::default(),
+            composition: None,
+            composing: false,
+            edited: false,
+            edit_start: usize::MAX,
+            edit_end: usize::MAX,
+            rect,
+            caret: Rect::ZERO,
+        };
+        session.update(state, rect);
+        session
+    }
+
+    /// Take the state of the view. While text is being composed the text of the view doesn't
+    /// have the composed text yet, so only where it is changes. Returns how the input method
+    /// should be told about the change.
+    pub(crate) fn update(
+        &mut self,
+        state: TextInputState,
+        rect: Rect,
+    ) -> Option<glazier::text::Event> {
+        let caret = state.caret + rect.origin().to_vec2();
+        let moved = caret != self.caret || rect != self.rect;
+        self.caret = caret;
+        self.rect = rect;
+        if !self.composing && (state.text != self.text || state.selection != self.view_selection) {
+            self.text = state.text.clone();
+            self.selection = Selection::new(state.selection.start, state.selection.end);
+            self.composition = None;
+            self.view_text = state.text;
+            self.view_selection = state.selection;
+            self.edit_start = usize::MAX;
+            self.edit_end = usize::MAX;
+            return Some(glazier::text::Event::Reset);
+        }
+        moved.then_some(glazier::text::Event::LayoutChanged)
+    }
+
+    /// The events that tell the view about the edits of the input method since the last time
+    pub(crate) fn take_events(&mut self) -> Vec<Event> {
+        if !std::mem::take(&mut self.edited) {
+            return Vec::new();
+        }
+        let mut events = Vec::new();
+        match self.composition.clone() {
+            Some(composition) => {
+                if !self.composing {
+                    self.composing = true;
+                    events.push(Event::CompositionStart);
+                }
+                let selection = self.selection.range();
+                let cursor = (composition.start <= selection.start
+                    && selection.end <= composition.end)
+                    .then(|| {
+                        selection.start - composition.start..selection.end - composition.start
+                    });
+                events.push(Event::CompositionUpdate {
+                    text: self.text[composition].to_string(),
+                    cursor,
+                });
+            }
+            None => {
+                let (range, text) =
+                    changed_range(&self.view_text, &self.text, self.edit_start, self.edit_end);
+                if self.composing || !range.is_empty() || !text.is_empty() {
+                    events.push(Event::CompositionCommit {
+                        text: text.to_string(),
+                        range,
+                    });
+                }
+                self.composing = false;
+                self.view_text = self.text.clone();
+                self.view_selection = self.selection.range();
+                self.edit_start = usize::MAX;
+                self.edit_end = usize::MAX;
+            }
+        }
+        events
+    }
+}
+
+/// The range of `old` that was replaced to get `new`, and what it was replaced with. The edits
+/// are known to be at least `edit_start` from the start of `new` and `edit_end` from its end,
+/// which tells where the change is when text is repeated.
+fn changed_range<'a>(
+    old: &str,
+    new: &'a str,
+    edit_start: usize,
+    edit_end: usize,
+) -> (Range<usize>, &'a str) {
+    let mut start = old
+        .bytes()
+        .zip(new.bytes())
+        .take_while(|(a, b)| a == b)
+        .count()
+        .min(edit_start);
+    while !old.is_char_boundary(start) {
+        start -= 1;
+    }
+    let max_suffix = (old.len() - start).min(new.len() - start).min(edit_end);
+    let mut suffix = old
+        .bytes()
+        .rev()
+        .zip(new.bytes().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(old.len() - suffix) || !new.is_char_boundary(new.len() - suffix) {
+        suffix -= 1;
+    }
+    (start..old.len() - suffix, &new[start..new.len() - suffix])
+}
+
+/// Lets the input method read and edit the text of the focused text input
+pub(crate) struct ImeHandler(pub(crate) Rc<RefCell<ImeSession>>);
+
+impl InputHandler for ImeHandler {
+    fn selection(&self) -> Selection {
+        self.0.borrow().selection
+    }
+
+    fn set_selection(&mut self, selection: Selection) {
+        let mut session = self.0.borrow_mut();
+        session.selection = selection;
+        session.edited = true;
+    }
+
+    fn composition_range(&self) -> Option<Range<usize>> {
+        self.0.borrow().composition.clone()
+    }
+
+    fn set_composition_range(&mut self, range: Option<Range<usize>>) {
+        let mut session = self.0.borrow_mut();
+        session.composition = range;
+        session.edited = true;
+    }
+
+    fn is_char_boundary(&self, i: usize) -> bool {
+        self.0.borrow().text.is_char_boundary(i)
+    }
+
+    fn len(&self) -> usize {
+        self.0.borrow().text.len()
+    }
+
+    fn slice(&self, range: Range<usize>) -> Cow<'_, str> {
+        Cow::Owned(self.0.borrow().text[range].to_string())
+    }
+
+    fn replace_range(&mut self, range: Range<usize>, text: &str) {
+        let mut session = self.0.borrow_mut();
+        let caret = range.start + text.len();
+        session.edit_start = session.edit_start.min(range.start);
+        session.text.replace_range(range, text);
+        session.edit_end = session.edit_end.min(session.text.len() - caret);
+        session.selection = Selection::caret(caret);
+        session.edited = true;
+    }
+
+    /// The layout of the text is up to the view, so a point in the view hits the caret
+    fn hit_test_point(&self, point: Point) -> HitTestPoint {
+        let session = self.0.borrow();
+        HitTestPoint {
+            idx: session.selection.active,
+            is_inside: session.rect.contains(point),
+        }
+    }
+
+    /// Text inputs are taken to be a single line
+    fn line_range(&self, _index: usize, _affinity: Affinity) -> Range<usize> {
+        0..self.0.borrow().text.len()
+    }
+
+    fn bounding_box(&self) -> Option<Rect> {
+        Some(self.0.borrow().rect)
+    }
+
+    /// The input method uses it to put its windows next to the text, for which the caret is
+    /// close enough
+    fn slice_bounding_box(&self, _range: Range<usize>) -> Option<Rect> {
+        Some(self.0.borrow().caret)
+    }
+
+    /// The view gets the editing keys as key events before the input method does, so it acts on
+    /// them itself
+    fn handle_action(&mut self, _action: Action) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use glazier::{
+        kurbo::Rect,
+        text::{InputHandler, Selection},
+    };
+
+    use super::{ImeHandler, ImeSession, TextInputState};
+    use crate::id::Id;
+
+    fn events(session: &Rc<RefCell<ImeSession>>) -> Vec<String> {
+        session
+            .borrow_mut()
+            .take_events()
+            .iter()
+            .map(|event| format!("{event:?}"))
+            .collect()
+    }
+
+    #[test]
+    fn composition_and_typing() {
+        let state = TextInputState {
+            text: "ab".to_string(),
+            selection: 1..1,
+            caret: Rect::ZERO,
+        };
+        let session = Rc::new(RefCell::new(ImeSession::new(Id::next(), state, Rect::ZERO)));
+        let mut handler = ImeHandler(session.clone());
+
+        // Composing replaces the selection with the composed text
+        handler.replace_range(1..1, "k");
+        handler.set_composition_range(Some(1..2));
+        assert_eq!(
+            events(&session),
+            [
+                "CompositionStart".to_string(),
+                "CompositionUpdate { text: \"k\", cursor: Some(1..1) }".to_string(),
+            ]
+        );
+
+        handler.replace_range(1..2, "か");
+        handler.set_composition_range(Some(1..4));
+        handler.set_selection(Selection::new(1, 4));
+        assert_eq!(
+            events(&session),
+            ["CompositionUpdate { text: \"か\", cursor: Some(0..3) }".to_string()]
+        );
+        assert_eq!(handler.slice(0..handler.len()), "aかb");
+
+        // Nothing changed
+        assert!(events(&session).is_empty());
+
+        handler.set_composition_range(None);
+        assert_eq!(
+            events(&session),
+            ["CompositionCommit { text: \"か\", range: 1..1 }".to_string()]
+        );
+
+        // Typing without composing, where the same letter is before the caret
+        handler.set_selection(Selection::caret(1));
+        handler.replace_range(1..1, "a");
+        assert_eq!(
+            events(&session),
+            ["CompositionCommit { text: \"a\", range: 1..1 }".to_string()]
+        );
+
+        // A cancelled composition still ends
+        handler.replace_range(2..2, "x");
+        handler.set_composition_range(Some(2..3));
+        assert_eq!(events(&session).len(), 2);
+        handler.replace_range(2..3, "");
+        handler.set_composition_range(None);
+        assert_eq!(
+            events(&session),
+            ["CompositionCommit { text: \"\", range: 2..2 }".to_string()]
+        );
+    }
+}
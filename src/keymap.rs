@@ -0,0 +1,367 @@
+//! # Keymap
+//! Commands are actions with an id, like `file.save`, that key bindings run. A binding is a
+//! sequence of key presses separated by spaces, where a press is a key with `+` separated
+//! modifiers before it:
+//! ```text
+//! Ctrl+S
+//! Ctrl+Shift+P
+//! Ctrl+K Ctrl+S
+//! Mod+W
+//! ```
+//! `Mod` is Cmd on macOS and Ctrl elsewhere. Keys are characters or the names of keys like
+//! `Enter`, `Escape`, `ArrowUp` and `F5`.
+//!
+//! A binding can be scoped to a view, so it only works while the focus is in that view. When
+//! several bindings match, the one scoped to the view closest to the focus wins, and bindings
+//! without a scope come last. Key presses go to the keymap before they are sent to the focused
+//! view.
+
+use std::{cell::RefCell, collections::HashMap, fmt, rc::Rc};
+
+use glazier::{KbKey, KeyEvent, Modifiers};
+
+use crate::id::Id;
+
+thread_local! {
+    pub(crate) static KEYMAP: RefCell<Keymap> = RefCell::new(Keymap::default());
+}
+
+const MODIFIERS: [Modifiers; 4] = [
+    Modifiers::CONTROL,
+    Modifiers::ALT,
+    Modifiers::SHIFT,
+    Modifiers::META,
+];
+
+/// The modifier that is written `Mod`
+#[cfg(target_os = "macos")]
+const MOD: Modifiers = Modifiers::META;
+#[cfg(not(target_os = "macos"))]
+const MOD: Modifiers = Modifiers::CONTROL;
+
+/// A key with the modifiers held down while pressing it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyPress {
+    pub key: KbKey,
+    pub mods: Modifiers,
+}
+
+impl KeyPress {
+    pub fn parse(text: &str) -> Result<Self, KeymapError> {
+        let error = || KeymapError::Parse(format!("invalid key press `{text}`"));
+        // The key itself can be `+`, like in `Ctrl++`
+        let (mods, key) = match text.strip_suffix("++") {
+            Some(mods) => (mods, "+"),
+            None => text.rsplit_once('+').unwrap_or(("", text)),
+        };
+
+        let mut press_mods = Modifiers::empty();
+        for modifier in mods.split('+').filter(|m| !m.is_empty()) {
+            let modifier = match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => Modifiers::CONTROL,
+                "alt" | "option" => Modifiers::ALT,
+                "shift" => Modifiers::SHIFT,
+                "meta" | "cmd" | "super" => Modifiers::META,
+                "mod" => MOD,
+                _ => return Err(error()),
+            };
+            press_mods.set(modifier, true);
+        }
+
+        let key = match key.to_lowercase().as_str() {
+            "" => return Err(error()),
+            "space" => KbKey::Character(" ".to_string()),
+            "esc" => KbKey::Escape,
+            "up" => KbKey::ArrowUp,
+            "down" => KbKey::ArrowDown,
+            "left" => KbKey::ArrowLeft,
+            "right" => KbKey::ArrowRight,
+            _ if key.chars().count() == 1 => KbKey::Character(key.to_lowercase()),
+            _ => key.parse().map_err(|_| error())?,
+        };
+
+        Ok(Self {
+            key,
+            mods: press_mods,
+        })
+    }
+
+    /// Whether the key event is this press. Letters match in either case, and Shift is ignored
+    /// for other characters, since it's often needed to type them.
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        let mut mods = Modifiers::empty();
+        for modifier in MODIFIERS {
+            mods.set(modifier, event.mods.contains(modifier));
+        }
+        match (&self.key, &event.key) {
+            (KbKey::Character(key), KbKey::Character(event_key)) => {
+                let is_letter = key.chars().all(char::is_alphabetic);
+                if !is_letter {
+                    mods.set(Modifiers::SHIFT, self.mods.contains(Modifiers::SHIFT));
+                }
+                key.to_lowercase() == event_key.to_lowercase() && mods == self.mods
+            }
+            (key, event_key) => key == event_key && mods == self.mods,
+        }
+    }
+}
+
+impl fmt::Display for KeyPress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in MODIFIERS.iter().zip(["Ctrl", "Alt", "Shift", "Meta"]) {
+            if self.mods.contains(*modifier) {
+                write!(f, "{name}+")?;
+            }
+        }
+        match &self.key {
+            KbKey::Character(c) if c == " " => write!(f, "Space"),
+            KbKey::Character(c) => write!(f, "{}", c.to_uppercase()),
+            key => write!(f, "{key}"),
+        }
+    }
+}
+
+/// A sequence of key presses, like `Ctrl+K Ctrl+S`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBinding(pub Vec<KeyPress>);
+
+impl KeyBinding {
+    pub fn parse(text: &str) -> Result<Self, KeymapError> {
+        let presses = text
+            .split_whitespace()
+            .map(KeyPress::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        if presses.is_empty() {
+            return Err(KeymapError::Parse("empty key binding".to_string()));
+        }
+        Ok(Self(presses))
+    }
+
+    /// Whether pressing the keys of either binding runs into the other one
+    fn overlaps(&self, other: &KeyBinding) -> bool {
+        self.0.iter().zip(other.0.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, press) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{press}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeymapError {
+    /// The text isn't a valid key binding
+    Parse(String),
+    /// A binding in the same scope is the same or starts the same way, so one of them could
+    /// never run
+    Conflict {
+        binding: String,
+        existing: String,
+        command: String,
+    },
+}
+
+impl fmt::Display for KeymapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeymapError::Parse(message) => write!(f, "{message}"),
+            KeymapError::Conflict {
+                binding,
+                existing,
+                command,
+            } => write!(
+                f,
+                "`{binding}` conflicts with `{existing}`, which is bound to `{command}`"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for KeymapError {}
+
+struct Binding {
+    keys: KeyBinding,
+    command: String,
+    scope: Option<Id>,
+}
+
+/// Commands and the key bindings that run them
+#[derive(Default)]
+pub struct Keymap {
+    commands: HashMap<String, Rc<dyn Fn()>>,
+    bindings: Vec<Binding>,
+    /// The presses of a binding with several presses typed so far
+    pending: Vec<KeyEvent>,
+}
+
+impl Keymap {
+    pub fn register_command(&mut self, id: &str, handler: impl Fn() + 'static) {
+        self.commands.insert(id.to_string(), Rc::new(handler));
+    }
+
+    /// Bind `keys` to the command, only while the focus is in the `scope` view if there is one
+    pub fn bind(
+        &mut self,
+        keys: &str,
+        command: &str,
+        scope: Option<Id>,
+    ) -> Result<(), KeymapError> {
+        let keys = KeyBinding::parse(keys)?;
+        let conflict = self
+            .bindings
+            .iter()
+            .find(|binding| binding.scope == scope && binding.keys.overlaps(&keys));
+        if let Some(conflict) = conflict {
+            return Err(KeymapError::Conflict {
+                binding: keys.to_string(),
+                existing: conflict.keys.to_string(),
+                command: conflict.command.clone(),
+            });
+        }
+        self.bindings.push(Binding {
+            keys,
+            command: command.to_string(),
+            scope,
+        });
+        Ok(())
+    }
+
+    /// Remove the bindings of the command
+    pub fn unbind(&mut self, command: &str) {
+        self.bindings.retain(|binding| binding.command != command);
+    }
+
+    pub(crate) fn command(&self, id: &str) -> Option<Rc<dyn Fn()>> {
+        self.commands.get(id).cloned()
+    }
+
+    /// Find what to do with a key press while the focus is in the views of `focus_path`, from
+    /// the root to the focused view. Returns whether the press was used, and the handler of the
+    /// command it completed, if any.
+    pub(crate) fn key_down(
+        &mut self,
+        event: &KeyEvent,
+        focus_path: &[Id],
+    ) -> (bool, Option<Rc<dyn Fn()>>) {
+        if let KbKey::Shift | KbKey::Control | KbKey::Alt | KbKey::Meta = event.key {
+            return (false, None);
+        }
+        let in_chord = !self.pending.is_empty();
+        self.pending.push(event.clone());
+
+        // How close the scope of a binding is to the focus, if it applies
+        let depth = |binding: &Binding| match binding.scope {
+            Some(scope) => focus_path.iter().position(|id| *id == scope).map(|i| i + 1),
+            None => Some(0),
+        };
+        let pending = &self.pending;
+        let matches = |binding: &&Binding| {
+            binding.keys.0.len() >= pending.len()
+                && binding
+                    .keys
+                    .0
+                    .iter()
+                    .zip(pending)
+                    .all(|(k, e)| k.matches(e))
+        };
+
+        let complete = self
+            .bindings
+            .iter()
+            .filter(matches)
+            .filter(|binding| binding.keys.0.len() == pending.len())
+            .filter_map(|binding| depth(binding).map(|depth| (depth, binding)))
+            .max_by_key(|(depth, _)| *depth)
+            .map(|(_, binding)| binding.command.clone());
+        if let Some(command) = complete {
+            self.pending.clear();
+            return (true, self.command(&command));
+        }
+
+        let partial = self
+            .bindings
+            .iter()
+            .filter(matches)
+            .any(|binding| depth(binding).is_some());
+        if partial {
+            return (true, None);
+        }
+        // A press that doesn't continue the chord ends it, and is used up by it
+        self.pending.clear();
+        (in_chord, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use glazier::{KbKey, KeyEvent, Modifiers};
+
+    use super::{KeyBinding, Keymap, KeymapError};
+    use crate::id::Id;
+
+    fn key(key: &str, mods: Modifiers) -> KeyEvent {
+        KeyEvent {
+            key: KbKey::Character(key.to_string()),
+            mods,
+            ..KeyEvent::default()
+        }
+    }
+
+    #[test]
+    fn parse_binding() {
+        let binding = KeyBinding::parse("Ctrl+K ctrl+shift+s").unwrap();
+        assert_eq!(binding.to_string(), "Ctrl+K Ctrl+Shift+S");
+        assert_eq!(KeyBinding::parse("Alt+F4").unwrap().to_string(), "Alt+F4");
+        assert_eq!(KeyBinding::parse("Ctrl++").unwrap().to_string(), "Ctrl++");
+        assert!(KeyBinding::parse("Hyper+K").is_err());
+        assert!(KeyBinding::parse("").is_err());
+    }
+
+    #[test]
+    fn chords_and_scopes() {
+        let ran = Rc::new(Cell::new(""));
+        let mut keymap = Keymap::default();
+        for command in ["save", "save_all", "close"] {
+            let ran = ran.clone();
+            keymap.register_command(command, move || ran.set(command));
+        }
+        let scope = Id::next();
+        keymap.bind("Ctrl+S", "save", None).unwrap();
+        keymap.bind("Ctrl+K Ctrl+S", "save_all", None).unwrap();
+        keymap.bind("Ctrl+S", "close", Some(scope)).unwrap();
+        assert!(matches!(
+            keymap.bind("Ctrl+K", "close", None),
+            Err(KeymapError::Conflict { .. })
+        ));
+
+        let ctrl = Modifiers::CONTROL;
+        let run = |keymap: &mut Keymap, event: KeyEvent, focus_path: &[Id]| {
+            let (used, handler) = keymap.key_down(&event, focus_path);
+            if let Some(handler) = handler {
+                handler();
+            }
+            used
+        };
+        assert!(run(&mut keymap, key("s", ctrl), &[]));
+        assert_eq!(ran.get(), "save");
+        assert!(run(&mut keymap, key("k", ctrl), &[]));
+        assert!(run(&mut keymap, key("S", ctrl), &[]));
+        assert_eq!(ran.get(), "save_all");
+        assert!(run(&mut keymap, key("s", ctrl), &[scope]));
+        assert_eq!(ran.get(), "close");
+
+        // A press that doesn't continue the chord cancels it
+        assert!(run(&mut keymap, key("k", ctrl), &[]));
+        assert!(run(&mut keymap, key("x", Modifiers::empty()), &[]));
+        assert!(!run(&mut keymap, key("x", Modifiers::empty()), &[]));
+    }
+}
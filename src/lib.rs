@@ -1,17 +1,27 @@
 pub mod app;
+pub mod clipboard;
 pub mod context;
 pub mod event;
 pub mod ext_event;
+pub mod gesture;
 pub mod id;
+pub mod ime;
+pub mod keymap;
+pub mod menu;
 pub mod renderer;
 pub mod style;
+#[cfg(feature = "serde")]
+mod style_serde;
+pub mod stylesheet;
 pub mod text;
+pub mod timer;
 pub mod view;
 pub mod view_tuple;
 pub mod views;
 
 pub use floem_renderer::cosmic_text;
 pub use floem_renderer::Renderer;
+pub use floem_renderer::TextSpacing;
 pub use glazier;
 use glazier::kurbo::Size;
 pub use leptos_reactive as reactive;
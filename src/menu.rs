@@ -0,0 +1,517 @@
+//! # Menus
+//! A [`Menu`] is built from items, separators and submenus.
+//! [`AppContext::show_context_menu`](crate::app::AppContext::show_context_menu) shows it as a
+//! native popup menu on Windows and macOS, and otherwise as a popup menu drawn in the window,
+//! which is used with the mouse or the arrow keys.
+
+use floem_renderer::Renderer;
+use glazier::{
+    kurbo::{Line, Point, Rect, Size},
+    KbKey, KeyEvent,
+};
+use vello::peniko::Color;
+
+use crate::{
+    context::PaintCx,
+    cosmic_text::{Attrs, AttrsList, TextLayout},
+    event::Event,
+    views::floating_origin,
+};
+
+pub type MenuAction = dyn Fn();
+
+/// Whether glazier shows popup menus natively on this platform. Its X11 and Wayland backends
+/// don't show them, so Linux uses [`MenuPopup`].
+pub(crate) const NATIVE_CONTEXT_MENUS: bool = cfg!(any(target_os = "windows", target_os = "macos"));
+
+const FONT_SIZE: f32 = 13.0;
+const POPUP_PADDING: f64 = 4.0;
+const ROW_PADDING: f64 = 4.0;
+const CHECK_WIDTH: f64 = 22.0;
+const ARROW_WIDTH: f64 = 22.0;
+const SEPARATOR_HEIGHT: f64 = 9.0;
+const MIN_WIDTH: f64 = 120.0;
+
+pub struct Menu {
+    title: String,
+    enabled: bool,
+    entries: Vec<MenuEntry>,
+}
+
+pub enum MenuEntry {
+    Item(MenuItem),
+    Separator,
+    SubMenu(Menu),
+}
+
+pub struct MenuItem {
+    title: String,
+    enabled: bool,
+    checked: Option<bool>,
+    action: Option<Box<MenuAction>>,
+}
+
+impl Menu {
+    /// The title is shown for the menu when it is a submenu
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            enabled: true,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn entry(mut self, entry: impl Into<MenuEntry>) -> Self {
+        self.entries.push(entry.into());
+        self
+    }
+
+    pub fn separator(mut self) -> Self {
+        self.entries.push(MenuEntry::Separator);
+        self
+    }
+
+    /// A disabled submenu can't be opened
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// The native menu. The ids of its items are their indices in `actions`.
+    pub(crate) fn platform_menu(self, actions: &mut Vec<Option<Box<MenuAction>>>) -> glazier::Menu {
+        let mut menu = glazier::Menu::new_for_popup();
+        self.add_platform_entries(&mut menu, actions);
+        menu
+    }
+
+    fn add_platform_entries(
+        self,
+        menu: &mut glazier::Menu,
+        actions: &mut Vec<Option<Box<MenuAction>>>,
+    ) {
+        for entry in self.entries {
+            match entry {
+                MenuEntry::Item(item) => {
+                    menu.add_item(
+                        actions.len() as u32,
+                        &item.title,
+                        None,
+                        item.checked,
+                        item.enabled,
+                    );
+                    actions.push(item.action);
+                }
+                MenuEntry::Separator => menu.add_separator(),
+                MenuEntry::SubMenu(submenu) => {
+                    let title = submenu.title.clone();
+                    let enabled = submenu.enabled;
+                    let mut platform_submenu = glazier::Menu::new(title.clone());
+                    submenu.add_platform_entries(&mut platform_submenu, actions);
+                    menu.add_dropdown(platform_submenu, &title, enabled);
+                }
+            }
+        }
+    }
+
+    fn submenu(&self, path: &[usize]) -> &Menu {
+        match path.split_first() {
+            Some((index, path)) => match &self.entries[*index] {
+                MenuEntry::SubMenu(submenu) => submenu.submenu(path),
+                _ => self,
+            },
+            None => self,
+        }
+    }
+}
+
+impl MenuItem {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            enabled: true,
+            checked: None,
+            action: None,
+        }
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Show a checkmark next to the item when `checked` is true
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.checked = Some(checked);
+        self
+    }
+
+    /// Run `action` when the item is chosen
+    pub fn action(mut self, action: impl Fn() + 'static) -> Self {
+        self.action = Some(Box::new(action));
+        self
+    }
+}
+
+impl From<MenuItem> for MenuEntry {
+    fn from(item: MenuItem) -> Self {
+        MenuEntry::Item(item)
+    }
+}
+
+impl From<Menu> for MenuEntry {
+    fn from(menu: Menu) -> Self {
+        MenuEntry::SubMenu(menu)
+    }
+}
+
+impl MenuEntry {
+    /// Whether the entry can be highlighted and chosen
+    fn is_selectable(&self) -> bool {
+        match self {
+            MenuEntry::Item(item) => item.enabled,
+            MenuEntry::Separator => false,
+            MenuEntry::SubMenu(menu) => menu.enabled,
+        }
+    }
+
+    fn title(&self) -> Option<(&str, bool)> {
+        match self {
+            MenuEntry::Item(item) => Some((&item.title, item.enabled)),
+            MenuEntry::Separator => None,
+            MenuEntry::SubMenu(menu) => Some((&menu.title, menu.enabled)),
+        }
+    }
+}
+
+/// The selectable entry after `from`, or before it when `forward` is false, wrapping around
+fn next_selectable(entries: &[MenuEntry], from: Option<usize>, forward: bool) -> Option<usize> {
+    let len = entries.len();
+    (1..=len)
+        .map(|step| match (from, forward) {
+            (Some(from), true) => (from + step) % len,
+            (Some(from), false) => (from + len - step % len) % len,
+            (None, true) => step - 1,
+            (None, false) => len - step,
+        })
+        .find(|index| entries[*index].is_selectable())
+}
+
+fn text_layout(text: &str, color: Color) -> TextLayout {
+    let mut layout = TextLayout::new();
+    layout.set_text(
+        text,
+        AttrsList::new(Attrs::new().color(color).font_size(FONT_SIZE)),
+    );
+    layout
+}
+
+/// One open menu of a [`MenuPopup`]
+struct PopupLevel {
+    /// The indices of the submenus that lead to the menu from the root menu
+    path: Vec<usize>,
+    /// Where the menu is in the window
+    rect: Rect,
+    /// The entries of the menu with their rects in the window
+    rows: Vec<(Rect, Option<TextLayout>)>,
+    highlighted: Option<usize>,
+}
+
+/// A popup menu drawn on top of the window, for platforms without native popup menus.
+/// It gets all the mouse and keyboard events while it is open.
+pub(crate) struct MenuPopup {
+    menu: Menu,
+    levels: Vec<PopupLevel>,
+    window: Size,
+    check: TextLayout,
+    arrow: TextLayout,
+}
+
+impl MenuPopup {
+    pub(crate) fn new(menu: Menu, pos: Point, window: Size) -> Self {
+        let mut popup = Self {
+            menu,
+            levels: Vec::new(),
+            window,
+            check: text_layout("✓", Color::BLACK),
+            arrow: text_layout("›", Color::BLACK),
+        };
+        popup.open(Vec::new(), |size| {
+            floating_origin(Rect::from_points(pos, pos), size, window, 0.0)
+        });
+        popup
+    }
+
+    /// Open the menu at `path`, at the origin `place` gives for its size
+    fn open(&mut self, path: Vec<usize>, place: impl FnOnce(Size) -> Point) {
+        let menu = self.menu.submenu(&path);
+        let layouts: Vec<Option<TextLayout>> = menu
+            .entries
+            .iter()
+            .map(|entry| {
+                entry.title().map(|(title, enabled)| {
+                    let color = if enabled {
+                        Color::BLACK
+                    } else {
+                        Color::rgb8(0xa0, 0xa0, 0xa0)
+                    };
+                    text_layout(title, color)
+                })
+            })
+            .collect();
+
+        let text_width = layouts
+            .iter()
+            .flatten()
+            .map(|layout| layout.size().width)
+            .fold(0.0, f64::max);
+        let width = (CHECK_WIDTH + text_width + ARROW_WIDTH).max(MIN_WIDTH);
+        let heights: Vec<f64> = layouts
+            .iter()
+            .map(|layout| match layout {
+                Some(layout) => layout.size().height + ROW_PADDING * 2.0,
+                None => SEPARATOR_HEIGHT,
+            })
+            .collect();
+        let size = Size::new(
+            width + POPUP_PADDING * 2.0,
+            heights.iter().sum::<f64>() + POPUP_PADDING * 2.0,
+        );
+        let origin = place(size);
+
+        let mut y = origin.y + POPUP_PADDING;
+        let rows = layouts
+            .into_iter()
+            .zip(heights)
+            .map(|(layout, height)| {
+                let x = origin.x + POPUP_PADDING;
+                let rect = Rect::new(x, y, x + width, y + height);
+                y += height;
+                (rect, layout)
+            })
+            .collect();
+        self.levels.push(PopupLevel {
+            path,
+            rect: Rect::from_origin_size(origin, size),
+            rows,
+            highlighted: None,
+        });
+    }
+
+    /// Open the submenu in `row` of the menu at `level` next to the row, closing the menus
+    /// after `level`
+    fn open_submenu(&mut self, level: usize, row: usize) {
+        self.levels.truncate(level + 1);
+        let mut path = self.levels[level].path.clone();
+        path.push(row);
+        let row_rect = self.levels[level].rows[row].0;
+        let level_rect = self.levels[level].rect;
+        let window = self.window;
+        self.open(path, |size| {
+            let mut x = level_rect.x1;
+            if x + size.width > window.width {
+                x = level_rect.x0 - size.width;
+            }
+            let mut y = row_rect.y0 - POPUP_PADDING;
+            if y + size.height > window.height {
+                y = window.height - size.height;
+            }
+            Point::new(x.max(0.0), y.max(0.0))
+        });
+    }
+
+    fn entries(&self, level: usize) -> &[MenuEntry] {
+        &self.menu.submenu(&self.levels[level].path).entries
+    }
+
+    /// The menu and the row of it at `pos`, looking at the menus opened last first
+    fn row_at(&self, pos: Point) -> Option<(usize, Option<usize>)> {
+        let level = self
+            .levels
+            .iter()
+            .rposition(|level| level.rect.contains(pos))?;
+        let row = self.levels[level]
+            .rows
+            .iter()
+            .position(|(rect, _)| rect.contains(pos));
+        Some((level, row))
+    }
+
+    /// Highlight `row` of the menu at `level` or open it when it is a submenu. Returns false
+    /// when the row was a chosen item, and the popup should close.
+    fn choose(&mut self, level: usize, row: usize) -> bool {
+        match &self.entries(level)[row] {
+            MenuEntry::Item(item) if item.enabled => {
+                if let Some(action) = item.action.as_ref() {
+                    action();
+                }
+                false
+            }
+            MenuEntry::SubMenu(menu) if menu.enabled => {
+                self.open_submenu(level, row);
+                let submenu = self.levels.len() - 1;
+                self.levels[submenu].highlighted =
+                    next_selectable(self.entries(submenu), None, true);
+                true
+            }
+            _ => true,
+        }
+    }
+
+    fn hover(&mut self, pos: Point) {
+        let (level, row) = match self.row_at(pos) {
+            Some((level, Some(row))) => (level, row),
+            _ => return,
+        };
+        let entry = &self.entries(level)[row];
+        let selectable = entry.is_selectable();
+        let is_submenu = matches!(entry, MenuEntry::SubMenu(_));
+        self.levels[level].highlighted = if selectable { Some(row) } else { None };
+        let submenu_open = self
+            .levels
+            .get(level + 1)
+            .map(|next| next.path.last() == Some(&row))
+            .unwrap_or(false);
+        if selectable && is_submenu {
+            if !submenu_open {
+                self.open_submenu(level, row);
+            }
+        } else {
+            self.levels.truncate(level + 1);
+        }
+    }
+
+    fn key_down(&mut self, event: &KeyEvent) -> bool {
+        let level = self.levels.len() - 1;
+        let highlighted = self.levels[level].highlighted;
+        match &event.key {
+            KbKey::ArrowDown | KbKey::ArrowUp => {
+                let forward = event.key == KbKey::ArrowDown;
+                self.levels[level].highlighted =
+                    next_selectable(self.entries(level), highlighted, forward);
+            }
+            KbKey::Home => {
+                self.levels[level].highlighted = next_selectable(self.entries(level), None, true);
+            }
+            KbKey::End => {
+                self.levels[level].highlighted = next_selectable(self.entries(level), None, false);
+            }
+            KbKey::ArrowRight => {
+                if let Some(row) = highlighted {
+                    if matches!(self.entries(level)[row], MenuEntry::SubMenu(_)) {
+                        return self.choose(level, row);
+                    }
+                }
+            }
+            KbKey::ArrowLeft if level > 0 => {
+                self.levels.pop();
+            }
+            KbKey::Escape => {
+                if level == 0 {
+                    return false;
+                }
+                self.levels.pop();
+            }
+            KbKey::Enter => {
+                if let Some(row) = highlighted {
+                    return self.choose(level, row);
+                }
+            }
+            KbKey::Character(c) if c == " " => {
+                if let Some(row) = highlighted {
+                    return self.choose(level, row);
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Returns whether the popup stays open after the event
+    pub(crate) fn event(&mut self, event: &Event) -> bool {
+        match event {
+            Event::MouseMove(mouse_event) => {
+                self.hover(mouse_event.pos);
+                true
+            }
+            // Pressing outside of the menus closes them
+            Event::MouseDown(mouse_event) => self.row_at(mouse_event.pos).is_some(),
+            Event::MouseUp(mouse_event) => match self.row_at(mouse_event.pos) {
+                Some((level, Some(row)))
+                    if !matches!(self.entries(level)[row], MenuEntry::SubMenu(_)) =>
+                {
+                    self.choose(level, row)
+                }
+                _ => true,
+            },
+            Event::KeyDown(key_event) => self.key_down(key_event),
+            Event::WindowResized(_) | Event::WindowLostFocus | Event::WindowClosed => false,
+            _ => true,
+        }
+    }
+
+    pub(crate) fn paint(&self, cx: &mut PaintCx) {
+        let border = Color::rgb8(0xc8, 0xc8, 0xc8);
+        for (index, level) in self.levels.iter().enumerate() {
+            let shape = level.rect.to_rounded_rect(4.0);
+            cx.fill(&shape, Color::WHITE);
+            cx.stroke(&shape, border, 1.0);
+            for (row, (entry, (rect, layout))) in self
+                .entries(index)
+                .iter()
+                .zip(level.rows.iter())
+                .enumerate()
+            {
+                let text_y = rect.y0 + ROW_PADDING;
+                match entry {
+                    MenuEntry::Separator => {
+                        let y = rect.center().y;
+                        cx.stroke(&Line::new((rect.x0, y), (rect.x1, y)), border, 1.0);
+                    }
+                    MenuEntry::Item(item) => {
+                        if level.highlighted == Some(row) {
+                            cx.fill(&rect.to_rounded_rect(3.0), Color::rgb8(0xd4, 0xe6, 0xff));
+                        }
+                        if item.checked == Some(true) {
+                            cx.draw_text(&self.check, Point::new(rect.x0 + 6.0, text_y));
+                        }
+                    }
+                    MenuEntry::SubMenu(_) => {
+                        if level.highlighted == Some(row) {
+                            cx.fill(&rect.to_rounded_rect(3.0), Color::rgb8(0xd4, 0xe6, 0xff));
+                        }
+                        cx.draw_text(&self.arrow, Point::new(rect.x1 - ARROW_WIDTH + 6.0, text_y));
+                    }
+                }
+                if let Some(layout) = layout {
+                    cx.draw_text(layout, Point::new(rect.x0 + CHECK_WIDTH, text_y));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{next_selectable, Menu, MenuItem};
+
+    #[test]
+    fn keyboard_navigation_skips_separators_and_disabled_items() {
+        let menu = Menu::new("")
+            .entry(MenuItem::new("Cut").enabled(false))
+            .entry(MenuItem::new("Copy"))
+            .separator()
+            .entry(Menu::new("More").entry(MenuItem::new("Share")))
+            .entry(MenuItem::new("Delete").enabled(false));
+        let entries = &menu.entries;
+
+        assert_eq!(next_selectable(entries, None, true), Some(1));
+        assert_eq!(next_selectable(entries, Some(1), true), Some(3));
+        // Wraps around
+        assert_eq!(next_selectable(entries, Some(3), true), Some(1));
+        assert_eq!(next_selectable(entries, None, false), Some(3));
+        assert_eq!(next_selectable(entries, Some(1), false), Some(3));
+
+        let menu = Menu::new("").entry(MenuItem::new("Cut").enabled(false));
+        assert_eq!(next_selectable(&menu.entries, None, true), None);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::cosmic_text::TextLayout;
+use floem_renderer::TextSpacing;
 use floem_vger::VgerRenderer;
 use glazier::{
     kurbo::{Affine, Rect, Shape, Size},
@@ -80,6 +81,19 @@ impl floem_renderer::Renderer for Renderer {
         }
     }
 
+    fn draw_text_with_spacing(
+        &mut self,
+        layout: &TextLayout,
+        pos: impl Into<glazier::kurbo::Point>,
+        spacing: &TextSpacing,
+    ) {
+        match self {
+            Renderer::Vger(v) => {
+                v.draw_text_with_spacing(layout, pos, spacing);
+            }
+        }
+    }
+
     fn draw_svg<'b>(
         &mut self,
         svg: floem_renderer::Svg<'b>,
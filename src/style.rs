@@ -25,18 +25,134 @@
 //! ```
 //!
 
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+};
+
 use floem_renderer::cosmic_text::{Style as FontStyle, Weight};
+use glazier::kurbo::Size;
 pub use taffy::style::{
-    AlignContent, AlignItems, Dimension, Display, FlexDirection, JustifyContent, Position,
+    AlignContent, AlignItems, AlignSelf, Dimension, Display, FlexDirection, FlexWrap,
+    JustifyContent, JustifySelf, Position,
 };
 use taffy::{
     prelude::Rect,
     style::{LengthPercentage, LengthPercentageAuto, Style as TaffyStyle},
 };
-use vello::peniko::Color;
+use vello::peniko::{Color, Image};
+
+#[cfg(feature = "serde")]
+use crate::style_serde::{value_from_repr, value_to_repr, StyleSerde};
+use crate::stylesheet::ParseStyleValue;
+
+/// The font size that [`Length::Em`] and [`Length::Rem`] are relative to when no view sets one
+pub const DEFAULT_FONT_SIZE: f32 = 12.0;
+
+/// A length for a [`Style`] property, which is resolved to points during layout.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Length {
+    /// Absolute points
+    Pt(f32),
+    /// A fraction of the parent's size, so `1.0` is 100%
+    Pct(f32),
+    /// Let the layout decide, e.g. `margin: auto` for centering. Resolves to zero for properties
+    /// that have no notion of `auto`, like padding and border.
+    Auto,
+    /// Multiples of the view's font size
+    Em(f32),
+    /// Multiples of the root view's font size
+    Rem(f32),
+    /// A fraction of the window width
+    Vw(f32),
+    /// A fraction of the window height
+    Vh(f32),
+}
+
+/// What relative [`Length`]s are resolved against.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LengthContext {
+    pub font_size: f32,
+    pub root_font_size: f32,
+    pub viewport: Size,
+}
+
+impl Default for LengthContext {
+    fn default() -> Self {
+        Self {
+            font_size: DEFAULT_FONT_SIZE,
+            root_font_size: DEFAULT_FONT_SIZE,
+            viewport: Size::ZERO,
+        }
+    }
+}
+
+impl Length {
+    /// The length in points, or `None` if it depends on the parent or on the layout
+    pub fn to_points(self, lcx: &LengthContext) -> Option<f32> {
+        match self {
+            Length::Pt(v) => Some(v),
+            Length::Pct(_) | Length::Auto => None,
+            Length::Em(v) => Some(v * lcx.font_size),
+            Length::Rem(v) => Some(v * lcx.root_font_size),
+            Length::Vw(v) => Some(v * lcx.viewport.width as f32),
+            Length::Vh(v) => Some(v * lcx.viewport.height as f32),
+        }
+    }
+
+    pub fn to_dimension(self, lcx: &LengthContext) -> Dimension {
+        match self {
+            Length::Pct(v) => Dimension::Percent(v),
+            Length::Auto => Dimension::Auto,
+            _ => Dimension::Points(self.to_points(lcx).unwrap_or(0.0)),
+        }
+    }
+
+    pub fn to_length_percentage(self, lcx: &LengthContext) -> LengthPercentage {
+        match self {
+            Length::Pct(v) => LengthPercentage::Percent(v),
+            _ => LengthPercentage::Points(self.to_points(lcx).unwrap_or(0.0)),
+        }
+    }
+
+    pub fn to_length_percentage_auto(self, lcx: &LengthContext) -> LengthPercentageAuto {
+        match self {
+            Length::Pct(v) => LengthPercentageAuto::Percent(v),
+            Length::Auto => LengthPercentageAuto::Auto,
+            _ => LengthPercentageAuto::Points(self.to_points(lcx).unwrap_or(0.0)),
+        }
+    }
+}
+
+impl From<f32> for Length {
+    fn from(v: f32) -> Self {
+        Length::Pt(v)
+    }
+}
+
+// Float literals fall back to `f64` when the target is generic, so `padding(10.0)` needs this
+impl From<f64> for Length {
+    fn from(v: f64) -> Self {
+        Length::Pt(v as f32)
+    }
+}
+
+impl From<Dimension> for Length {
+    fn from(v: Dimension) -> Self {
+        match v {
+            Dimension::Points(v) => Length::Pt(v),
+            Dimension::Percent(v) => Length::Pct(v),
+            Dimension::Auto => Length::Auto,
+        }
+    }
+}
 
 /// The value for a [`Style`] property
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StyleValue<T> {
     Val(T),
     /// Use the default value for the style, typically from the underlying `ReifiedStyle`
@@ -55,6 +171,10 @@ impl<T> StyleValue<T> {
         }
     }
 
+    pub fn is_base(&self) -> bool {
+        matches!(self, Self::Base)
+    }
+
     pub fn unwrap_or(self, default: T) -> T {
         match self {
             Self::Val(x) => x,
@@ -70,6 +190,19 @@ impl<T> StyleValue<T> {
             Self::Base => f(),
         }
     }
+
+    /// The value of a property after a style setting it to `over` is applied on top
+    pub fn apply(self, over: StyleValue<T>) -> StyleValue<T> {
+        match (self, over) {
+            (_, StyleValue::Val(x)) => StyleValue::Val(x),
+            (StyleValue::Val(x), StyleValue::Base) => StyleValue::Val(x),
+            (StyleValue::Val(_), StyleValue::Unset) => StyleValue::Unset,
+            (StyleValue::Base, StyleValue::Base) => StyleValue::Base,
+            (StyleValue::Unset, StyleValue::Base) => StyleValue::Unset,
+            (StyleValue::Base, StyleValue::Unset) => StyleValue::Unset,
+            (StyleValue::Unset, StyleValue::Unset) => StyleValue::Unset,
+        }
+    }
 }
 
 impl<T> Default for StyleValue<T> {
@@ -85,6 +218,260 @@ impl<T> From<T> for StyleValue<T> {
     }
 }
 
+impl From<f32> for StyleValue<Length> {
+    fn from(x: f32) -> Self {
+        Self::Val(x.into())
+    }
+}
+
+impl From<f64> for StyleValue<Length> {
+    fn from(x: f64) -> Self {
+        Self::Val(x.into())
+    }
+}
+
+impl From<Dimension> for StyleValue<Length> {
+    fn from(x: Dimension) -> Self {
+        Self::Val(x.into())
+    }
+}
+
+/// The mouse cursor shown while hovering a view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CursorStyle {
+    #[default]
+    Default,
+    Pointer,
+    Text,
+    Grab,
+    Grabbing,
+    ColResize,
+    RowResize,
+    NotAllowed,
+    Crosshair,
+}
+
+/// One layer of the background of a view
+#[derive(Debug, Clone, PartialEq)]
+pub enum Background {
+    Solid(Color),
+    /// A gradient along the line through the center at `angle` degrees clockwise from the top,
+    /// spanning the box like CSS `linear-gradient`
+    LinearGradient {
+        angle: f64,
+        stops: Vec<(f32, Color)>,
+    },
+    /// A gradient from the center out to the farthest corner
+    RadialGradient {
+        stops: Vec<(f32, Color)>,
+    },
+    /// A gradient around the center, starting at `start_angle` degrees clockwise from the top
+    SweepGradient {
+        start_angle: f64,
+        stops: Vec<(f32, Color)>,
+    },
+    Image(BackgroundImage),
+}
+
+impl From<Color> for Background {
+    fn from(color: Color) -> Self {
+        Background::Solid(color)
+    }
+}
+
+impl From<BackgroundImage> for Background {
+    fn from(image: BackgroundImage) -> Self {
+        Background::Image(image)
+    }
+}
+
+/// An image drawn from the top left corner of the box
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackgroundImage {
+    pub image: Image,
+    pub size: BackgroundSize,
+    /// Tile the image to fill the box
+    pub repeat: bool,
+}
+
+impl BackgroundImage {
+    pub fn new(image: Image) -> Self {
+        Self {
+            image,
+            size: BackgroundSize::Auto,
+            repeat: false,
+        }
+    }
+
+    pub fn size(mut self, size: BackgroundSize) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn repeat(mut self, repeat: bool) -> Self {
+        self.repeat = repeat;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BackgroundSize {
+    /// The size of the image in pixels
+    Auto,
+    /// Scale the image to cover the whole box, keeping its aspect ratio
+    Cover,
+    /// Scale the image to fit inside the box, keeping its aspect ratio
+    Contain,
+    Size(f64, f64),
+}
+
+impl BackgroundSize {
+    /// The horizontal and vertical scale of an image of `image_size` in a box of `size`
+    pub fn scale(&self, image_size: Size, size: Size) -> (f64, f64) {
+        let x = size.width / image_size.width;
+        let y = size.height / image_size.height;
+        match *self {
+            BackgroundSize::Auto => (1.0, 1.0),
+            BackgroundSize::Cover => (x.max(y), x.max(y)),
+            BackgroundSize::Contain => (x.min(y), x.min(y)),
+            BackgroundSize::Size(width, height) => {
+                (width / image_size.width, height / image_size.height)
+            }
+        }
+    }
+}
+
+/// A range of window widths that a responsive style applies to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Breakpoint {
+    /// Narrower than the width
+    Below(f64),
+    /// At least as wide as the width
+    Above(f64),
+    /// At least as wide as the first width and narrower than the second
+    Between(f64, f64),
+}
+
+impl Breakpoint {
+    pub fn contains(&self, width: f64) -> bool {
+        match *self {
+            Breakpoint::Below(max) => width < max,
+            Breakpoint::Above(min) => width >= min,
+            Breakpoint::Between(min, max) => width >= min && width < max,
+        }
+    }
+}
+
+/// How content that doesn't fit in the box of a view is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Overflow {
+    Visible,
+    /// Clip the content to the box
+    Hidden,
+    /// Clip the content, and let it be scrolled with a scroll bar shown
+    Scroll,
+    /// Like `Scroll`, but the scroll bar is only shown when the content overflows
+    Auto,
+}
+
+impl Overflow {
+    pub fn is_clipped(self) -> bool {
+        self != Overflow::Visible
+    }
+
+    pub fn is_scrollable(self) -> bool {
+        matches!(self, Overflow::Scroll | Overflow::Auto)
+    }
+}
+
+/// How lines of text are aligned within the box of the view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextAlign {
+    Start,
+    Center,
+    End,
+    /// Stretch the spaces of each line to fill the width, except on the last line of a paragraph
+    Justify,
+}
+
+/// How white space in text is handled and whether lines wrap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WhiteSpace {
+    /// Collapse runs of white space and wrap lines to the width of the view
+    Wrap,
+    /// Collapse runs of white space, including newlines, and keep the text on a single line
+    NoWrap,
+    /// Keep white space and newlines as they are, without wrapping
+    Pre,
+}
+
+/// How text that doesn't fit in the width of the view is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextOverflow {
+    /// Cut the text off at the edge of the view
+    Clip,
+    /// Replace the end of the text with "..."
+    Ellipsis,
+    /// Fade out the text towards the edge of the view
+    Fade,
+}
+
+/// A style property that isn't one of the built in fields, so custom views can have their own
+/// properties go through the same cascade of styles, hover styles and stylesheets.
+///
+/// ```ignore
+/// struct SelectionColor;
+/// impl StyleProp for SelectionColor {
+///     type Type = Color;
+///     const NAME: &'static str = "selection_color";
+///     fn default_value() -> Color {
+///         Color::BLUE
+///     }
+/// }
+///
+/// let style = Style::default().prop::<SelectionColor>(Color::RED);
+/// ```
+///
+/// Custom properties aren't serialized with the `serde` feature, as their types are unknown, so
+/// a deserialized style doesn't have them.
+pub trait StyleProp: 'static {
+    type Type: Clone + 'static;
+    /// The name of the property in stylesheets
+    const NAME: &'static str;
+    fn default_value() -> Self::Type;
+}
+
+type ParseCustom = fn(&str) -> Option<Rc<dyn Any>>;
+
+thread_local! {
+    /// The custom properties that stylesheets can set, by name
+    static CUSTOM_PROPS: RefCell<HashMap<String, (TypeId, ParseCustom)>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Make a custom property settable from stylesheets
+pub fn register_style_prop<P: StyleProp>()
+where
+    P::Type: ParseStyleValue,
+{
+    fn parse<P: StyleProp>(value: &str) -> Option<Rc<dyn Any>>
+    where
+        P::Type: ParseStyleValue,
+    {
+        P::Type::parse_style_value(value).map(|v| Rc::new(v) as Rc<dyn Any>)
+    }
+    CUSTOM_PROPS.with(|props| {
+        props
+            .borrow_mut()
+            .insert(P::NAME.replace('-', "_"), (TypeId::of::<P>(), parse::<P>))
+    });
+}
+
 // Creates `ReifiedStyle` which has definite values for the fields, barring some specific cases.
 // Creates `Style` which has `StyleValue<T>`s for the fields
 macro_rules! define_styles {
@@ -97,6 +484,8 @@ macro_rules! define_styles {
             $(
                 pub $name: $typ,
             )*
+            /// The values of [`StyleProp`]s, which are their defaults when missing
+            pub(crate) custom: HashMap<TypeId, Rc<dyn Any>>,
         }
         impl ReifiedStyle {
             $(
@@ -112,6 +501,7 @@ macro_rules! define_styles {
                     $(
                         $name: $val,
                     )*
+                    custom: HashMap::new(),
                 }
             }
         }
@@ -121,6 +511,8 @@ macro_rules! define_styles {
             $(
                 pub $name: StyleValue<$typ>,
             )*
+            /// The values of [`StyleProp`]s, which are `Base` when missing
+            pub(crate) custom: HashMap<TypeId, StyleValue<Rc<dyn Any>>>,
         }
         impl Style {
             pub fn unset() -> Self {
@@ -128,6 +520,7 @@ macro_rules! define_styles {
                     $(
                         $name: StyleValue::Unset,
                     )*
+                    custom: HashMap::new(),
                 }
             }
 
@@ -137,6 +530,7 @@ macro_rules! define_styles {
                     $(
                         $name: StyleValue::Base,
                     )*
+                    custom: HashMap::new(),
                 }
             }
 
@@ -147,10 +541,17 @@ macro_rules! define_styles {
             /// Convert this `Style` into a reified style, using the given `ReifiedStyle` as a base
             /// for any missing values.
             pub fn reify(self, underlying: &ReifiedStyle) -> ReifiedStyle {
+                let mut custom = underlying.custom.clone();
+                for (id, value) in self.custom {
+                    if let StyleValue::Val(value) = value {
+                        custom.insert(id, value);
+                    }
+                }
                 ReifiedStyle {
                     $(
                         $name: self.$name.unwrap_or_else(|| underlying.$name.clone()),
                     )*
+                    custom,
                 }
             }
 
@@ -162,18 +563,16 @@ macro_rules! define_styles {
             /// `StyleValue::Base` will leave the value as-is, whether falling back to the underlying
             /// `ReifiedStyle` or using the value in the `Style`.
             pub fn apply(self, over: Style) -> Style {
+                let mut custom = self.custom;
+                for (id, value) in over.custom {
+                    let base = custom.remove(&id).unwrap_or_default();
+                    custom.insert(id, base.apply(value));
+                }
                 Style {
                     $(
-                        $name: match (self.$name, over.$name) {
-                            (_, StyleValue::Val(x)) => StyleValue::Val(x),
-                            (StyleValue::Val(x), StyleValue::Base) => StyleValue::Val(x),
-                            (StyleValue::Val(_), StyleValue::Unset) => StyleValue::Unset,
-                            (StyleValue::Base, StyleValue::Base) => StyleValue::Base,
-                            (StyleValue::Unset, StyleValue::Base) => StyleValue::Unset,
-                            (StyleValue::Base, StyleValue::Unset) => StyleValue::Unset,
-                            (StyleValue::Unset, StyleValue::Unset) => StyleValue::Unset,
-                        },
+                        $name: self.$name.apply(over.$name),
                     )*
+                    custom,
                 }
             }
 
@@ -182,6 +581,109 @@ macro_rules! define_styles {
             pub fn apply_overriding_styles(self, overrides: impl Iterator<Item = Style>) -> Style {
                 overrides.fold(self, |acc, x| acc.apply(x))
             }
+
+            /// Set the field called `name` from the text of a stylesheet value, like `8px` or
+            /// `#ff0000`.
+            pub fn set_property(&mut self, name: &str, value: &str) -> Result<(), String> {
+                match name {
+                    $(
+                        stringify!($name) => {
+                            let v = <$typ as ParseStyleValue>::parse_style_value(value)
+                                .ok_or_else(|| format!("invalid value `{value}` for `{name}`"))?;
+                            self.$name = StyleValue::Val(v);
+                        }
+                    )*
+                    _ => {
+                        let custom = CUSTOM_PROPS.with(|props| props.borrow().get(name).copied());
+                        let (id, parse) =
+                            custom.ok_or_else(|| format!("unknown property `{name}`"))?;
+                        let v = parse(value)
+                            .ok_or_else(|| format!("invalid value `{value}` for `{name}`"))?;
+                        self.custom.insert(id, StyleValue::Val(v));
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        /// The serialized form of a `ReifiedStyle`, where missing fields are the defaults
+        #[cfg(feature = "serde")]
+        #[derive(serde::Serialize, serde::Deserialize)]
+        #[serde(default)]
+        struct ReifiedStyleRepr {
+            $(
+                $name: <$typ as StyleSerde>::Repr,
+            )*
+        }
+        #[cfg(feature = "serde")]
+        impl Default for ReifiedStyleRepr {
+            fn default() -> Self {
+                let style = ReifiedStyle::default();
+                Self {
+                    $(
+                        $name: style.$name.to_repr(),
+                    )*
+                }
+            }
+        }
+        /// Custom properties and image background layers are left out
+        #[cfg(feature = "serde")]
+        impl serde::Serialize for ReifiedStyle {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                ReifiedStyleRepr {
+                    $(
+                        $name: self.$name.to_repr(),
+                    )*
+                }
+                .serialize(serializer)
+            }
+        }
+        #[cfg(feature = "serde")]
+        impl<'de> serde::Deserialize<'de> for ReifiedStyle {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let repr = ReifiedStyleRepr::deserialize(deserializer)?;
+                Ok(ReifiedStyle {
+                    $(
+                        $name: StyleSerde::from_repr(repr.$name)
+                            .map_err(serde::de::Error::custom)?,
+                    )*
+                    custom: HashMap::new(),
+                })
+            }
+        }
+
+        /// The serialized form of a `Style`, where `Base` fields are left out
+        #[cfg(feature = "serde")]
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct StyleRepr {
+            $(
+                #[serde(default, skip_serializing_if = "StyleValue::is_base")]
+                $name: StyleValue<<$typ as StyleSerde>::Repr>,
+            )*
+        }
+        /// Custom properties and image background layers are left out
+        #[cfg(feature = "serde")]
+        impl serde::Serialize for Style {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                StyleRepr {
+                    $(
+                        $name: value_to_repr(&self.$name),
+                    )*
+                }
+                .serialize(serializer)
+            }
+        }
+        #[cfg(feature = "serde")]
+        impl<'de> serde::Deserialize<'de> for Style {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let repr = StyleRepr::deserialize(deserializer)?;
+                Ok(Style {
+                    $(
+                        $name: value_from_repr(repr.$name).map_err(serde::de::Error::custom)?,
+                    )*
+                    custom: HashMap::new(),
+                })
+            }
         }
     };
     // internal submacro
@@ -198,63 +700,96 @@ macro_rules! define_styles {
 
 define_styles!(
     display: Display = Display::Flex,
+    overflow_x: Overflow = Overflow::Visible,
+    overflow_y: Overflow = Overflow::Visible,
     position: Position = Position::Relative,
-    width: Dimension = Dimension::Auto,
-    height: Dimension = Dimension::Auto,
-    min_width: Dimension = Dimension::Auto,
-    min_height: Dimension = Dimension::Auto,
-    max_width: Dimension = Dimension::Auto,
-    max_height: Dimension = Dimension::Auto,
+    width: Length = Length::Auto,
+    height: Length = Length::Auto,
+    min_width: Length = Length::Auto,
+    min_height: Length = Length::Auto,
+    max_width: Length = Length::Auto,
+    max_height: Length = Length::Auto,
+    inset_left: Length = Length::Auto,
+    inset_top: Length = Length::Auto,
+    inset_right: Length = Length::Auto,
+    inset_bottom: Length = Length::Auto,
+    aspect_ratio: Option<f32> = None,
     flex_direction: FlexDirection = FlexDirection::Row,
+    flex_wrap: FlexWrap = FlexWrap::NoWrap,
     flex_grow: f32 = 0.0,
     flex_shrink: f32 = 1.0,
-    flex_basis: Dimension = Dimension::Auto,
+    flex_basis: Length = Length::Auto,
     justify_content: Option<JustifyContent> = None,
     align_items: Option<AlignItems> = None,
     align_content: Option<AlignContent> = None,
-    border_left: f32 = 0.0,
-    border_top: f32 = 0.0,
-    border_right: f32 = 0.0,
-    border_bottom: f32 = 0.0,
+    align_self: Option<AlignSelf> = None,
+    justify_self: Option<JustifySelf> = None,
+    column_gap: Length = Length::Pt(0.0),
+    row_gap: Length = Length::Pt(0.0),
+    border_left: Length = Length::Pt(0.0),
+    border_top: Length = Length::Pt(0.0),
+    border_right: Length = Length::Pt(0.0),
+    border_bottom: Length = Length::Pt(0.0),
     border_radius: f32 = 0.0,
     border_color: Color = Color::BLACK,
-    padding_left: f32 = 0.0,
-    padding_top: f32 = 0.0,
-    padding_right: f32 = 0.0,
-    padding_bottom: f32 = 0.0,
-    margin_left: f32 = 0.0,
-    margin_top: f32 = 0.0,
-    margin_right: f32 = 0.0,
-    margin_bottom: f32 = 0.0,
+    border_left_color nocb: Option<Color> = None,
+    border_top_color nocb: Option<Color> = None,
+    border_right_color nocb: Option<Color> = None,
+    border_bottom_color nocb: Option<Color> = None,
+    outline_width: f32 = 0.0,
+    outline_color: Color = Color::BLACK,
+    outline_offset: f32 = 0.0,
+    z_index: i32 = 0,
+    cursor nocb: Option<CursorStyle> = None,
+    padding_left: Length = Length::Pt(0.0),
+    padding_top: Length = Length::Pt(0.0),
+    padding_right: Length = Length::Pt(0.0),
+    padding_bottom: Length = Length::Pt(0.0),
+    margin_left: Length = Length::Pt(0.0),
+    margin_top: Length = Length::Pt(0.0),
+    margin_right: Length = Length::Pt(0.0),
+    margin_bottom: Length = Length::Pt(0.0),
     color nocb: Option<Color> = None,
-    background nocb: Option<Color> = None,
+    background nocb: Vec<Background> = Vec::new(),
     font_size nocb: Option<f32> = None,
     font_family nocb: Option<String> = None,
     font_weight nocb: Option<Weight> = None,
     font_style nocb: Option<FontStyle> = None,
+    line_height nocb: Option<f32> = None,
+    letter_spacing nocb: Option<f32> = None,
+    text_align nocb: Option<TextAlign> = None,
+    white_space nocb: Option<WhiteSpace> = None,
+    text_overflow nocb: Option<TextOverflow> = None,
 );
 
 impl Style {
+    /// Set a custom [`StyleProp`]
+    pub fn prop<P: StyleProp>(mut self, v: impl Into<StyleValue<P::Type>>) -> Self {
+        let v = v.into().map(|v| Rc::new(v) as Rc<dyn Any>);
+        self.custom.insert(TypeId::of::<P>(), v);
+        self
+    }
+
     pub fn width_pt(self, width: f32) -> Self {
-        self.width(Dimension::Points(width))
+        self.width(Length::Pt(width))
     }
 
     pub fn width_pct(self, width: f32) -> Self {
-        self.width(Dimension::Percent(width))
+        self.width(Length::Pct(width))
     }
 
     pub fn height_pt(self, height: f32) -> Self {
-        self.height(Dimension::Points(height))
+        self.height(Length::Pt(height))
     }
 
     pub fn height_pct(self, height: f32) -> Self {
-        self.height(Dimension::Percent(height))
+        self.height(Length::Pct(height))
     }
 
     pub fn dimension(
         self,
-        width: impl Into<StyleValue<Dimension>>,
-        height: impl Into<StyleValue<Dimension>>,
+        width: impl Into<StyleValue<Length>>,
+        height: impl Into<StyleValue<Length>>,
     ) -> Self {
         self.width(width).height(height)
     }
@@ -268,25 +803,25 @@ impl Style {
     }
 
     pub fn min_width_pt(self, min_width: f32) -> Self {
-        self.min_width(Dimension::Points(min_width))
+        self.min_width(Length::Pt(min_width))
     }
 
     pub fn min_width_pct(self, min_width: f32) -> Self {
-        self.min_width(Dimension::Percent(min_width))
+        self.min_width(Length::Pct(min_width))
     }
 
     pub fn min_height_pt(self, min_height: f32) -> Self {
-        self.min_height(Dimension::Points(min_height))
+        self.min_height(Length::Pt(min_height))
     }
 
     pub fn min_height_pct(self, min_height: f32) -> Self {
-        self.min_height(Dimension::Percent(min_height))
+        self.min_height(Length::Pct(min_height))
     }
 
     pub fn min_dimension(
         self,
-        min_width: impl Into<StyleValue<Dimension>>,
-        min_height: impl Into<StyleValue<Dimension>>,
+        min_width: impl Into<StyleValue<Length>>,
+        min_height: impl Into<StyleValue<Length>>,
     ) -> Self {
         self.min_width(min_width).min_height(min_height)
     }
@@ -300,25 +835,25 @@ impl Style {
     }
 
     pub fn max_width_pt(self, max_width: f32) -> Self {
-        self.max_width(Dimension::Points(max_width))
+        self.max_width(Length::Pt(max_width))
     }
 
     pub fn max_width_pct(self, max_width: f32) -> Self {
-        self.max_width(Dimension::Percent(max_width))
+        self.max_width(Length::Pct(max_width))
     }
 
     pub fn max_height_pt(self, max_height: f32) -> Self {
-        self.max_height(Dimension::Points(max_height))
+        self.max_height(Length::Pt(max_height))
     }
 
     pub fn max_height_pct(self, max_height: f32) -> Self {
-        self.max_height(Dimension::Percent(max_height))
+        self.max_height(Length::Pct(max_height))
     }
 
     pub fn max_dimension(
         self,
-        max_width: impl Into<StyleValue<Dimension>>,
-        max_height: impl Into<StyleValue<Dimension>>,
+        max_width: impl Into<StyleValue<Length>>,
+        max_height: impl Into<StyleValue<Length>>,
     ) -> Self {
         self.max_width(max_width).max_height(max_height)
     }
@@ -331,7 +866,63 @@ impl Style {
         self.max_width_pct(max_width).max_height_pct(max_height)
     }
 
-    pub fn border(self, border: f32) -> Self {
+    pub fn inset_left_pt(self, inset: f32) -> Self {
+        self.inset_left(Length::Pt(inset))
+    }
+
+    pub fn inset_left_pct(self, inset: f32) -> Self {
+        self.inset_left(Length::Pct(inset))
+    }
+
+    pub fn inset_top_pt(self, inset: f32) -> Self {
+        self.inset_top(Length::Pt(inset))
+    }
+
+    pub fn inset_top_pct(self, inset: f32) -> Self {
+        self.inset_top(Length::Pct(inset))
+    }
+
+    pub fn inset_right_pt(self, inset: f32) -> Self {
+        self.inset_right(Length::Pt(inset))
+    }
+
+    pub fn inset_right_pct(self, inset: f32) -> Self {
+        self.inset_right(Length::Pct(inset))
+    }
+
+    pub fn inset_bottom_pt(self, inset: f32) -> Self {
+        self.inset_bottom(Length::Pt(inset))
+    }
+
+    pub fn inset_bottom_pct(self, inset: f32) -> Self {
+        self.inset_bottom(Length::Pct(inset))
+    }
+
+    /// Sets all four insets to `inset`
+    pub fn inset(self, inset: impl Into<Length>) -> Self {
+        let inset = inset.into();
+        self.inset_left(inset)
+            .inset_top(inset)
+            .inset_right(inset)
+            .inset_bottom(inset)
+    }
+
+    pub fn inset_pt(self, inset: f32) -> Self {
+        self.inset(Length::Pt(inset))
+    }
+
+    pub fn inset_pct(self, inset: f32) -> Self {
+        self.inset(Length::Pct(inset))
+    }
+
+    /// Sets `column_gap` and `row_gap` to `gap`
+    pub fn gap(self, gap: impl Into<Length>) -> Self {
+        let gap = gap.into();
+        self.column_gap(gap).row_gap(gap)
+    }
+
+    pub fn border(self, border: impl Into<Length>) -> Self {
+        let border = border.into();
         self.border_left(border)
             .border_top(border)
             .border_right(border)
@@ -339,16 +930,19 @@ impl Style {
     }
 
     /// Sets `border_left` and `border_right` to `border`
-    pub fn border_horiz(self, border: f32) -> Self {
+    pub fn border_horiz(self, border: impl Into<Length>) -> Self {
+        let border = border.into();
         self.border_left(border).border_right(border)
     }
 
     /// Sets `border_top` and `border_bottom` to `border`
-    pub fn border_vert(self, border: f32) -> Self {
+    pub fn border_vert(self, border: impl Into<Length>) -> Self {
+        let border = border.into();
         self.border_top(border).border_bottom(border)
     }
 
-    pub fn padding(self, padding: f32) -> Self {
+    pub fn padding(self, padding: impl Into<Length>) -> Self {
+        let padding = padding.into();
         self.padding_left(padding)
             .padding_top(padding)
             .padding_right(padding)
@@ -356,16 +950,19 @@ impl Style {
     }
 
     /// Sets `padding_left` and `padding_right` to `padding`
-    pub fn padding_horiz(self, padding: f32) -> Self {
+    pub fn padding_horiz(self, padding: impl Into<Length>) -> Self {
+        let padding = padding.into();
         self.padding_left(padding).padding_right(padding)
     }
 
     /// Sets `padding_top` and `padding_bottom` to `padding`
-    pub fn padding_vert(self, padding: f32) -> Self {
+    pub fn padding_vert(self, padding: impl Into<Length>) -> Self {
+        let padding = padding.into();
         self.padding_top(padding).padding_bottom(padding)
     }
 
-    pub fn margin(self, margin: f32) -> Self {
+    pub fn margin(self, margin: impl Into<Length>) -> Self {
+        let margin = margin.into();
         self.margin_left(margin)
             .margin_top(margin)
             .margin_right(margin)
@@ -373,22 +970,64 @@ impl Style {
     }
 
     /// Sets `margin_left` and `margin_right` to `margin`
-    pub fn margin_horiz(self, margin: f32) -> Self {
+    pub fn margin_horiz(self, margin: impl Into<Length>) -> Self {
+        let margin = margin.into();
         self.margin_left(margin).margin_right(margin)
     }
 
     /// Sets `margin_top` and `margin_bottom` to `margin`
-    pub fn margin_vert(self, margin: f32) -> Self {
+    pub fn margin_vert(self, margin: impl Into<Length>) -> Self {
+        let margin = margin.into();
         self.margin_top(margin).margin_bottom(margin)
     }
 
+    /// Set the color of the left border, instead of using `border_color`
+    pub fn border_left_color(mut self, color: impl Into<StyleValue<Color>>) -> Self {
+        self.border_left_color = color.into().map(Some);
+        self
+    }
+
+    /// Set the color of the top border, instead of using `border_color`
+    pub fn border_top_color(mut self, color: impl Into<StyleValue<Color>>) -> Self {
+        self.border_top_color = color.into().map(Some);
+        self
+    }
+
+    /// Set the color of the right border, instead of using `border_color`
+    pub fn border_right_color(mut self, color: impl Into<StyleValue<Color>>) -> Self {
+        self.border_right_color = color.into().map(Some);
+        self
+    }
+
+    /// Set the color of the bottom border, instead of using `border_color`
+    pub fn border_bottom_color(mut self, color: impl Into<StyleValue<Color>>) -> Self {
+        self.border_bottom_color = color.into().map(Some);
+        self
+    }
+
+    pub fn cursor(mut self, cursor: impl Into<StyleValue<CursorStyle>>) -> Self {
+        self.cursor = cursor.into().map(Some);
+        self
+    }
+
+    /// An outline is drawn outside the border, and doesn't take up any space in the layout
+    pub fn outline(self, width: f32, color: Color) -> Self {
+        self.outline_width(width).outline_color(color)
+    }
+
     pub fn color(mut self, color: impl Into<StyleValue<Color>>) -> Self {
         self.color = color.into().map(Some);
         self
     }
 
-    pub fn background(mut self, color: impl Into<StyleValue<Color>>) -> Self {
-        self.background = color.into().map(Some);
+    pub fn background(mut self, background: impl Into<Background>) -> Self {
+        self.background = StyleValue::Val(vec![background.into()]);
+        self
+    }
+
+    /// Layered backgrounds, with the first painted on top
+    pub fn backgrounds(mut self, backgrounds: impl Into<StyleValue<Vec<Background>>>) -> Self {
+        self.background = backgrounds.into();
         self
     }
 
@@ -412,6 +1051,45 @@ impl Style {
         self
     }
 
+    /// Set the line height as a multiple of the font size
+    pub fn line_height(mut self, height: impl Into<StyleValue<f32>>) -> Self {
+        self.line_height = height.into().map(Some);
+        self
+    }
+
+    /// Add space after every letter, in points
+    pub fn letter_spacing(mut self, spacing: impl Into<StyleValue<f32>>) -> Self {
+        self.letter_spacing = spacing.into().map(Some);
+        self
+    }
+
+    pub fn text_align(mut self, align: impl Into<StyleValue<TextAlign>>) -> Self {
+        self.text_align = align.into().map(Some);
+        self
+    }
+
+    pub fn white_space(mut self, white_space: impl Into<StyleValue<WhiteSpace>>) -> Self {
+        self.white_space = white_space.into().map(Some);
+        self
+    }
+
+    pub fn text_overflow(mut self, overflow: impl Into<StyleValue<TextOverflow>>) -> Self {
+        self.text_overflow = overflow.into().map(Some);
+        self
+    }
+
+    pub fn text_center(self) -> Self {
+        self.text_align(TextAlign::Center)
+    }
+
+    pub fn text_wrap(self) -> Self {
+        self.white_space(WhiteSpace::Wrap)
+    }
+
+    pub fn overflow(self, overflow: Overflow) -> Self {
+        self.overflow_x(overflow).overflow_y(overflow)
+    }
+
     pub fn absolute(self) -> Self {
         self.position(Position::Absolute)
     }
@@ -420,12 +1098,16 @@ impl Style {
         self.align_items(Some(AlignItems::Center))
     }
 
+    pub fn self_center(self) -> Self {
+        self.align_self(Some(AlignSelf::Center))
+    }
+
     pub fn justify_center(self) -> Self {
         self.justify_content(Some(JustifyContent::Center))
     }
 
     pub fn flex_basis_pt(self, pt: f32) -> Self {
-        self.flex_basis(Dimension::Points(pt))
+        self.flex_basis(Length::Pt(pt))
     }
 
     pub fn flex_row(self) -> Self {
@@ -436,6 +1118,10 @@ impl Style {
         self.flex_direction(FlexDirection::Column)
     }
 
+    pub fn flex_wrap_wrap(self) -> Self {
+        self.flex_wrap(FlexWrap::Wrap)
+    }
+
     /// Allow the application of a function if the option exists.  
     /// This is useful for chaining together a bunch of optional style changes.  
     /// ```rust,ignore
@@ -470,46 +1156,119 @@ impl Style {
 }
 
 impl ReifiedStyle {
-    pub fn to_taffy_style(&self) -> TaffyStyle {
+    /// The value of a custom [`StyleProp`], or its default if no style sets it
+    pub fn get<P: StyleProp>(&self) -> P::Type {
+        self.custom
+            .get(&TypeId::of::<P>())
+            .and_then(|v| v.downcast_ref::<P::Type>())
+            .cloned()
+            .unwrap_or_else(P::default_value)
+    }
+
+    /// Set a custom [`StyleProp`], like for the defaults returned by [`View::view_style`]
+    ///
+    /// [`View::view_style`]: crate::view::View::view_style
+    pub fn prop<P: StyleProp>(mut self, v: P::Type) -> Self {
+        self.custom.insert(TypeId::of::<P>(), Rc::new(v));
+        self
+    }
+
+    /// Whether changing from this style to `other` affects layout, rather than only how the view
+    /// is painted
+    pub fn layout_differs(&self, other: &ReifiedStyle, lcx: &LengthContext) -> bool {
+        // Custom properties might be used in layout, and their values can't be compared, so
+        // they count as changed unless they are the same value
+        let custom_differs = self.custom.len() != other.custom.len()
+            || self.custom.iter().any(|(id, v)| {
+                other
+                    .custom
+                    .get(id)
+                    .map(|other| !Rc::ptr_eq(v, other))
+                    .unwrap_or(true)
+            });
+        // Text properties are inherited by the children, which lay out their text with them
+        self.to_taffy_style(lcx) != other.to_taffy_style(lcx)
+            || self.text_layout_differs(other)
+            || custom_differs
+    }
+
+    /// Whether the text properties, which the children inherit to lay out their text, differ in
+    /// `other`
+    pub fn text_layout_differs(&self, other: &ReifiedStyle) -> bool {
+        self.font_size != other.font_size
+            || self.font_family != other.font_family
+            || self.font_weight != other.font_weight
+            || self.font_style != other.font_style
+            || self.line_height != other.line_height
+            || self.letter_spacing != other.letter_spacing
+            || self.text_align != other.text_align
+            || self.white_space != other.white_space
+            || self.text_overflow != other.text_overflow
+    }
+
+    /// Convert to a taffy style, resolving any relative [`Length`]s against `lcx`
+    pub fn to_taffy_style(&self, lcx: &LengthContext) -> TaffyStyle {
+        // Like in CSS, a box that clips its content doesn't grow to fit it by default
+        let min_size = |min: Length, overflow: Overflow| match min {
+            Length::Auto if overflow.is_clipped() => Dimension::Points(0.0),
+            min => min.to_dimension(lcx),
+        };
+        // Borders can't be relative to the parent size, since they're painted by the view itself
+        let border =
+            |length: Length| LengthPercentage::Points(length.to_points(lcx).unwrap_or(0.0));
         TaffyStyle {
             display: self.display,
             position: self.position,
+            inset: Rect {
+                left: self.inset_left.to_length_percentage_auto(lcx),
+                top: self.inset_top.to_length_percentage_auto(lcx),
+                right: self.inset_right.to_length_percentage_auto(lcx),
+                bottom: self.inset_bottom.to_length_percentage_auto(lcx),
+            },
             size: taffy::prelude::Size {
-                width: self.width,
-                height: self.height,
+                width: self.width.to_dimension(lcx),
+                height: self.height.to_dimension(lcx),
             },
             min_size: taffy::prelude::Size {
-                width: self.min_width,
-                height: self.min_height,
+                width: min_size(self.min_width, self.overflow_x),
+                height: min_size(self.min_height, self.overflow_y),
             },
             max_size: taffy::prelude::Size {
-                width: self.max_width,
-                height: self.max_height,
+                width: self.max_width.to_dimension(lcx),
+                height: self.max_height.to_dimension(lcx),
             },
+            aspect_ratio: self.aspect_ratio,
             flex_direction: self.flex_direction,
+            flex_wrap: self.flex_wrap,
             flex_grow: self.flex_grow,
             flex_shrink: self.flex_shrink,
-            flex_basis: self.flex_basis,
+            flex_basis: self.flex_basis.to_dimension(lcx),
             justify_content: self.justify_content,
             align_items: self.align_items,
             align_content: self.align_content,
+            align_self: self.align_self,
+            justify_self: self.justify_self,
+            gap: taffy::prelude::Size {
+                width: self.column_gap.to_length_percentage(lcx),
+                height: self.row_gap.to_length_percentage(lcx),
+            },
             border: Rect {
-                left: LengthPercentage::Points(self.border_left),
-                top: LengthPercentage::Points(self.border_top),
-                right: LengthPercentage::Points(self.border_right),
-                bottom: LengthPercentage::Points(self.border_bottom),
+                left: border(self.border_left),
+                top: border(self.border_top),
+                right: border(self.border_right),
+                bottom: border(self.border_bottom),
             },
             padding: Rect {
-                left: LengthPercentage::Points(self.padding_left),
-                top: LengthPercentage::Points(self.padding_top),
-                right: LengthPercentage::Points(self.padding_right),
-                bottom: LengthPercentage::Points(self.padding_bottom),
+                left: self.padding_left.to_length_percentage(lcx),
+                top: self.padding_top.to_length_percentage(lcx),
+                right: self.padding_right.to_length_percentage(lcx),
+                bottom: self.padding_bottom.to_length_percentage(lcx),
             },
             margin: Rect {
-                left: LengthPercentageAuto::Points(self.margin_left),
-                top: LengthPercentageAuto::Points(self.margin_top),
-                right: LengthPercentageAuto::Points(self.margin_right),
-                bottom: LengthPercentageAuto::Points(self.margin_bottom),
+                left: self.margin_left.to_length_percentage_auto(lcx),
+                top: self.margin_top.to_length_percentage_auto(lcx),
+                right: self.margin_right.to_length_percentage_auto(lcx),
+                bottom: self.margin_bottom.to_length_percentage_auto(lcx),
             },
             ..Default::default()
         }
@@ -518,7 +1277,114 @@ impl ReifiedStyle {
 
 #[cfg(test)]
 mod tests {
-    use super::{Style, StyleValue};
+    use glazier::kurbo::Size;
+    use taffy::style::{LengthPercentage, LengthPercentageAuto};
+    use vello::peniko::Color;
+
+    use super::{
+        register_style_prop, Dimension, Length, LengthContext, ReifiedStyle, Style, StyleProp,
+        StyleValue,
+    };
+
+    struct GutterWidth;
+
+    impl StyleProp for GutterWidth {
+        type Type = f32;
+        const NAME: &'static str = "gutter_width";
+
+        fn default_value() -> f32 {
+            10.0
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn style_serde() {
+        use super::{Background, BackgroundImage};
+        use vello::peniko::{Format, Image};
+
+        let style = Style::default()
+            .padding_left(8.0)
+            .border_color(Color::RED)
+            .padding_bottom(StyleValue::Unset);
+        let json = serde_json::to_string(&style).unwrap();
+        let style: Style = serde_json::from_str(&json).unwrap();
+        assert_eq!(style.padding_left, StyleValue::Val(Length::Pt(8.0)));
+        assert_eq!(style.border_color, StyleValue::Val(Color::RED));
+        assert_eq!(style.padding_bottom, StyleValue::Unset);
+        assert_eq!(style.padding_top, StyleValue::Base);
+
+        let style: ReifiedStyle = serde_json::from_str(r##"{"color": "#0000ffff"}"##).unwrap();
+        assert_eq!(style.color, Some(Color::BLUE));
+        assert_eq!(style.flex_shrink, 1.0);
+
+        // Image layers are left out, and the other layers read back the same
+        let image = Image::new(vec![0; 4].into(), Format::Rgba8, 1, 1);
+        let style = Style::default().backgrounds(vec![
+            Background::Image(BackgroundImage::new(image)),
+            Background::Solid(Color::RED),
+        ]);
+        let json = serde_json::to_string(&style).unwrap();
+        let style: Style = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            style.background,
+            StyleValue::Val(vec![Background::Solid(Color::RED)])
+        );
+    }
+
+    #[test]
+    fn layout_differs() {
+        let lcx = LengthContext::default();
+        let base = Style::default()
+            .padding_left(4.0)
+            .reify(&ReifiedStyle::default());
+        let hover = Style::default()
+            .background(Color::RED)
+            .border_color(Color::BLUE)
+            .reify(&base);
+        assert!(!base.layout_differs(&hover, &lcx));
+        let hover = Style::default().padding_left(8.0).reify(&base);
+        assert!(base.layout_differs(&hover, &lcx));
+        let hover = Style::default().font_size(20.0).reify(&base);
+        assert!(base.layout_differs(&hover, &lcx));
+    }
+
+    #[test]
+    fn text_layout_differs() {
+        let base = Style::default()
+            .padding_left(4.0)
+            .reify(&ReifiedStyle::default());
+        let hover = Style::default().padding_left(8.0).reify(&base);
+        assert!(!base.text_layout_differs(&hover));
+        let hover = Style::default().color(Color::RED).reify(&base);
+        assert!(!base.text_layout_differs(&hover));
+        let hover = Style::default().letter_spacing(1.0).reify(&base);
+        assert!(base.text_layout_differs(&hover));
+    }
+
+    #[test]
+    fn custom_prop() {
+        let base = ReifiedStyle::default();
+        assert_eq!(Style::default().reify(&base).get::<GutterWidth>(), 10.0);
+
+        let style = Style::default().prop::<GutterWidth>(20.0);
+        let hover = Style::default();
+        assert_eq!(
+            style.clone().apply(hover).reify(&base).get::<GutterWidth>(),
+            20.0
+        );
+        let hover = Style::default().prop::<GutterWidth>(StyleValue::Unset);
+        let underlying = base.clone().prop::<GutterWidth>(5.0);
+        assert_eq!(
+            style.apply(hover).reify(&underlying).get::<GutterWidth>(),
+            5.0
+        );
+
+        register_style_prop::<GutterWidth>();
+        let mut style = Style::default();
+        style.set_property("gutter_width", "30").unwrap();
+        assert_eq!(style.reify(&base).get::<GutterWidth>(), 30.0);
+    }
 
     #[test]
     fn style_override() {
@@ -527,7 +1393,7 @@ mod tests {
 
         let style = style1.apply(style2);
 
-        assert_eq!(style.padding_left, StyleValue::Val(64.0));
+        assert_eq!(style.padding_left, StyleValue::Val(Length::Pt(64.0)));
 
         let style1 = Style::default().padding_left(32.0).padding_bottom(45.0);
         let style2 = Style::default()
@@ -536,8 +1402,8 @@ mod tests {
 
         let style = style1.apply(style2);
 
-        assert_eq!(style.padding_left, StyleValue::Val(64.0));
-        assert_eq!(style.padding_bottom, StyleValue::Val(45.0));
+        assert_eq!(style.padding_left, StyleValue::Val(Length::Pt(64.0)));
+        assert_eq!(style.padding_bottom, StyleValue::Val(Length::Pt(45.0)));
 
         let style1 = Style::default().padding_left(32.0).padding_bottom(45.0);
         let style2 = Style::default()
@@ -546,7 +1412,7 @@ mod tests {
 
         let style = style1.apply(style2);
 
-        assert_eq!(style.padding_left, StyleValue::Val(64.0));
+        assert_eq!(style.padding_left, StyleValue::Val(Length::Pt(64.0)));
         assert_eq!(style.padding_bottom, StyleValue::Unset);
 
         let style1 = Style::default().padding_left(32.0).padding_bottom(45.0);
@@ -557,18 +1423,48 @@ mod tests {
 
         let style = style1.apply_overriding_styles([style2, style3].into_iter());
 
-        assert_eq!(style.padding_left, StyleValue::Val(64.0));
+        assert_eq!(style.padding_left, StyleValue::Val(Length::Pt(64.0)));
         assert_eq!(style.padding_bottom, StyleValue::Unset);
 
         let style1 = Style::default().padding_left(32.0).padding_bottom(45.0);
         let style2 = Style::default()
             .padding_left(64.0)
             .padding_bottom(StyleValue::Unset);
-        let style3 = Style::default().padding_bottom(StyleValue::Val(100.0));
+        let style3 = Style::default().padding_bottom(StyleValue::Val(Length::Pt(100.0)));
 
         let style = style1.apply_overriding_styles([style2, style3].into_iter());
 
-        assert_eq!(style.padding_left, StyleValue::Val(64.0));
-        assert_eq!(style.padding_bottom, StyleValue::Val(100.0));
+        assert_eq!(style.padding_left, StyleValue::Val(Length::Pt(64.0)));
+        assert_eq!(style.padding_bottom, StyleValue::Val(Length::Pt(100.0)));
+    }
+
+    #[test]
+    fn length_resolution() {
+        let lcx = LengthContext {
+            font_size: 20.0,
+            root_font_size: 10.0,
+            viewport: Size::new(800.0, 600.0),
+        };
+
+        assert_eq!(Length::Pt(5.0).to_points(&lcx), Some(5.0));
+        assert_eq!(Length::Em(1.5).to_points(&lcx), Some(30.0));
+        assert_eq!(Length::Rem(1.5).to_points(&lcx), Some(15.0));
+        assert_eq!(Length::Vw(0.5).to_points(&lcx), Some(400.0));
+        assert_eq!(Length::Vh(0.5).to_points(&lcx), Some(300.0));
+        assert_eq!(Length::Pct(0.5).to_points(&lcx), None);
+
+        let style = Style::default()
+            .padding(Length::Em(1.0))
+            .margin_horiz(Length::Auto)
+            .width_pct(0.5)
+            .border(Length::Pct(0.5))
+            .reify(&ReifiedStyle::default())
+            .to_taffy_style(&lcx);
+
+        assert_eq!(style.padding.left, LengthPercentage::Points(20.0));
+        assert_eq!(style.margin.left, LengthPercentageAuto::Auto);
+        assert_eq!(style.margin.top, LengthPercentageAuto::Points(0.0));
+        assert_eq!(style.size.width, Dimension::Percent(0.5));
+        assert_eq!(style.border.left, LengthPercentage::Points(0.0));
     }
 }
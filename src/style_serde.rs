@@ -0,0 +1,219 @@
+//! # Style serialization
+//! With the `serde` feature, [`Style`](crate::style::Style), [`StyleValue`] and
+//! [`ReifiedStyle`](crate::style::ReifiedStyle) can be serialized, to store themes or dump
+//! computed styles.
+//!
+//! Some property types come from crates without serde support, so every property type is
+//! converted to a serializable representation: colors are written like `#rrggbbaa`, font weights
+//! as numbers and font styles as `normal`, `italic` or `oblique`. Fields that are `Base` are left
+//! out of a serialized `Style`, and missing fields are read back as `Base`.
+//!
+//! Custom [`StyleProp`](crate::style::StyleProp)s and image background layers aren't serialized:
+//! they are left out, so what is kept reads back the same.
+
+use floem_renderer::cosmic_text::{Style as FontStyle, Weight};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use vello::peniko::Color;
+
+use crate::{
+    style::{
+        AlignContent, AlignItems, Background, CursorStyle, Display, FlexDirection, FlexWrap,
+        Length, Overflow, Position, StyleValue, TextAlign, TextOverflow, WhiteSpace,
+    },
+    stylesheet::ParseStyleValue,
+};
+
+/// A style property type and the form it is serialized in
+pub trait StyleSerde: Sized {
+    type Repr: Serialize + DeserializeOwned;
+
+    fn to_repr(&self) -> Self::Repr;
+
+    fn from_repr(repr: Self::Repr) -> Result<Self, String>;
+}
+
+macro_rules! serde_as_self {
+    ($($typ:ty),* $(,)?) => {
+        $(
+            impl StyleSerde for $typ {
+                type Repr = $typ;
+
+                fn to_repr(&self) -> $typ {
+                    self.clone()
+                }
+
+                fn from_repr(repr: $typ) -> Result<$typ, String> {
+                    Ok(repr)
+                }
+            }
+        )*
+    };
+}
+
+serde_as_self!(
+    f32,
+    i32,
+    String,
+    Length,
+    Display,
+    Position,
+    FlexDirection,
+    FlexWrap,
+    AlignItems,
+    AlignContent,
+    Overflow,
+    CursorStyle,
+    TextAlign,
+    WhiteSpace,
+    TextOverflow,
+);
+
+impl<T: StyleSerde> StyleSerde for Option<T> {
+    type Repr = Option<T::Repr>;
+
+    fn to_repr(&self) -> Self::Repr {
+        self.as_ref().map(T::to_repr)
+    }
+
+    fn from_repr(repr: Self::Repr) -> Result<Self, String> {
+        repr.map(T::from_repr).transpose()
+    }
+}
+
+impl StyleSerde for Color {
+    type Repr = String;
+
+    fn to_repr(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+    }
+
+    fn from_repr(repr: String) -> Result<Self, String> {
+        Color::parse_style_value(&repr).ok_or_else(|| format!("invalid color `{repr}`"))
+    }
+}
+
+impl StyleSerde for Weight {
+    type Repr = u16;
+
+    fn to_repr(&self) -> u16 {
+        self.0
+    }
+
+    fn from_repr(repr: u16) -> Result<Self, String> {
+        Ok(Weight(repr))
+    }
+}
+
+impl StyleSerde for FontStyle {
+    type Repr = String;
+
+    fn to_repr(&self) -> String {
+        match self {
+            FontStyle::Normal => "normal",
+            FontStyle::Italic => "italic",
+            FontStyle::Oblique => "oblique",
+        }
+        .to_string()
+    }
+
+    fn from_repr(repr: String) -> Result<Self, String> {
+        FontStyle::parse_style_value(&repr).ok_or_else(|| format!("invalid font style `{repr}`"))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackgroundRepr {
+    Solid(String),
+    LinearGradient {
+        angle: f64,
+        stops: Vec<(f32, String)>,
+    },
+    RadialGradient {
+        stops: Vec<(f32, String)>,
+    },
+    SweepGradient {
+        start_angle: f64,
+        stops: Vec<(f32, String)>,
+    },
+}
+
+fn stops_to_repr(stops: &[(f32, Color)]) -> Vec<(f32, String)> {
+    stops
+        .iter()
+        .map(|(offset, color)| (*offset, color.to_repr()))
+        .collect()
+}
+
+fn stops_from_repr(stops: Vec<(f32, String)>) -> Result<Vec<(f32, Color)>, String> {
+    stops
+        .into_iter()
+        .map(|(offset, color)| Ok((offset, Color::from_repr(color)?)))
+        .collect()
+}
+
+/// The image layers are left out, as the pixels of images aren't serialized
+impl StyleSerde for Vec<Background> {
+    type Repr = Vec<BackgroundRepr>;
+
+    fn to_repr(&self) -> Vec<BackgroundRepr> {
+        self.iter().filter_map(background_to_repr).collect()
+    }
+
+    fn from_repr(repr: Vec<BackgroundRepr>) -> Result<Self, String> {
+        repr.into_iter().map(background_from_repr).collect()
+    }
+}
+
+fn background_to_repr(background: &Background) -> Option<BackgroundRepr> {
+    Some(match background {
+        Background::Solid(color) => BackgroundRepr::Solid(color.to_repr()),
+        Background::LinearGradient { angle, stops } => BackgroundRepr::LinearGradient {
+            angle: *angle,
+            stops: stops_to_repr(stops),
+        },
+        Background::RadialGradient { stops } => BackgroundRepr::RadialGradient {
+            stops: stops_to_repr(stops),
+        },
+        Background::SweepGradient { start_angle, stops } => BackgroundRepr::SweepGradient {
+            start_angle: *start_angle,
+            stops: stops_to_repr(stops),
+        },
+        Background::Image(_) => return None,
+    })
+}
+
+fn background_from_repr(repr: BackgroundRepr) -> Result<Background, String> {
+    Ok(match repr {
+        BackgroundRepr::Solid(color) => Background::Solid(Color::from_repr(color)?),
+        BackgroundRepr::LinearGradient { angle, stops } => Background::LinearGradient {
+            angle,
+            stops: stops_from_repr(stops)?,
+        },
+        BackgroundRepr::RadialGradient { stops } => Background::RadialGradient {
+            stops: stops_from_repr(stops)?,
+        },
+        BackgroundRepr::SweepGradient { start_angle, stops } => Background::SweepGradient {
+            start_angle,
+            stops: stops_from_repr(stops)?,
+        },
+    })
+}
+
+pub(crate) fn value_to_repr<T: StyleSerde>(value: &StyleValue<T>) -> StyleValue<T::Repr> {
+    match value {
+        StyleValue::Val(v) => StyleValue::Val(v.to_repr()),
+        StyleValue::Unset => StyleValue::Unset,
+        StyleValue::Base => StyleValue::Base,
+    }
+}
+
+pub(crate) fn value_from_repr<T: StyleSerde>(
+    value: StyleValue<T::Repr>,
+) -> Result<StyleValue<T>, String> {
+    Ok(match value {
+        StyleValue::Val(v) => StyleValue::Val(T::from_repr(v)?),
+        StyleValue::Unset => StyleValue::Unset,
+        StyleValue::Base => StyleValue::Base,
+    })
+}
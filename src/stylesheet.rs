@@ -0,0 +1,632 @@
+//! # StyleSheet
+//! Styles can also be loaded from text in a small CSS-like syntax, so they can be tweaked without
+//! recompiling. A rule applies to the views tagged with its class through
+//! [`Decorators::class`](crate::views::Decorators::class):
+//! ```css
+//! /* Property names are the fields of `Style`, with `-` or `_` */
+//! .button {
+//!     padding-left: 8px;
+//!     padding_right: 8px;
+//!     border-radius: 4;
+//!     background: #e0e0e0;
+//! }
+//!
+//! .button:hover, .button:focus {
+//!     background: #c0c0ff;
+//! }
+//! ```
+//! Rules are applied in the order they appear, with `:hover`, `:focus` and `:active` rules after
+//! the plain class rules. The view's own [`Style`] is applied on top of the stylesheet, like an
+//! inline style in CSS.
+
+use std::{
+    fmt,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime},
+};
+
+use floem_renderer::cosmic_text::{Style as FontStyle, Weight};
+use leptos_reactive::{create_effect, create_signal, on_cleanup, ReadSignal, SignalGet, SignalSet};
+use vello::peniko::Color;
+
+use crate::{
+    app::AppContext,
+    context::InteractionState,
+    ext_event::create_signal_from_channel,
+    style::{
+        AlignContent, AlignItems, Background, CursorStyle, Display, FlexDirection, FlexWrap,
+        Length, Overflow, Position, Style, TextAlign, TextOverflow, WhiteSpace,
+    },
+};
+
+/// How often a watched stylesheet file is checked for modifications
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A style property value that can be written in a stylesheet
+pub trait ParseStyleValue: Sized {
+    fn parse_style_value(value: &str) -> Option<Self>;
+}
+
+impl ParseStyleValue for f32 {
+    fn parse_style_value(value: &str) -> Option<Self> {
+        value.parse().ok()
+    }
+}
+
+impl ParseStyleValue for i32 {
+    fn parse_style_value(value: &str) -> Option<Self> {
+        value.parse().ok()
+    }
+}
+
+impl ParseStyleValue for String {
+    fn parse_style_value(value: &str) -> Option<Self> {
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        Some(value.to_string())
+    }
+}
+
+impl<T: ParseStyleValue> ParseStyleValue for Option<T> {
+    fn parse_style_value(value: &str) -> Option<Self> {
+        T::parse_style_value(value).map(Some)
+    }
+}
+
+impl ParseStyleValue for Length {
+    fn parse_style_value(value: &str) -> Option<Self> {
+        let number = |s: &str| s.trim().parse::<f32>().ok();
+        if value == "auto" {
+            Some(Length::Auto)
+        } else if let Some(v) = value.strip_suffix('%') {
+            number(v).map(|v| Length::Pct(v / 100.0))
+        } else if let Some(v) = value.strip_suffix("rem") {
+            number(v).map(Length::Rem)
+        } else if let Some(v) = value.strip_suffix("em") {
+            number(v).map(Length::Em)
+        } else if let Some(v) = value.strip_suffix("vw") {
+            number(v).map(|v| Length::Vw(v / 100.0))
+        } else if let Some(v) = value.strip_suffix("vh") {
+            number(v).map(|v| Length::Vh(v / 100.0))
+        } else if let Some(v) = value
+            .strip_suffix("px")
+            .or_else(|| value.strip_suffix("pt"))
+        {
+            number(v).map(Length::Pt)
+        } else {
+            number(value).map(Length::Pt)
+        }
+    }
+}
+
+impl ParseStyleValue for Color {
+    fn parse_style_value(value: &str) -> Option<Self> {
+        let hex = value.strip_prefix('#')?;
+        if !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize, len: usize| {
+            let digits = hex.get(i * len..(i + 1) * len)?;
+            let v = u8::from_str_radix(digits, 16).ok()?;
+            // `#abc` is short for `#aabbcc`
+            Some(if len == 1 { v * 17 } else { v })
+        };
+        let (len, alpha) = match hex.len() {
+            3 => (1, false),
+            4 => (1, true),
+            6 => (2, false),
+            8 => (2, true),
+            _ => return None,
+        };
+        let a = if alpha { channel(3, len)? } else { 255 };
+        Some(Color::rgba8(
+            channel(0, len)?,
+            channel(1, len)?,
+            channel(2, len)?,
+            a,
+        ))
+    }
+}
+
+/// Split at the commas that aren't inside parentheses
+fn split_top_level(value: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(value[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(value[start..].trim());
+    parts
+}
+
+fn parse_angle(value: &str) -> Option<f64> {
+    value.strip_suffix("deg")?.trim().parse().ok()
+}
+
+/// Color stops like `#fff, #000 80%`, where stops without a position are spread evenly
+fn parse_color_stops(stops: &[&str]) -> Option<Vec<(f32, Color)>> {
+    let count = stops.len();
+    if count < 2 {
+        return None;
+    }
+    stops
+        .iter()
+        .enumerate()
+        .map(|(i, stop)| {
+            let mut parts = stop.split_whitespace();
+            let color = Color::parse_style_value(parts.next()?)?;
+            let offset = match parts.next() {
+                Some(offset) => offset.strip_suffix('%')?.parse::<f32>().ok()? / 100.0,
+                None => i as f32 / (count - 1) as f32,
+            };
+            Some((offset, color))
+        })
+        .collect()
+}
+
+impl ParseStyleValue for Background {
+    fn parse_style_value(value: &str) -> Option<Self> {
+        let function = |name: &str| {
+            value
+                .strip_prefix(name)
+                .and_then(|v| v.trim_start().strip_prefix('('))
+                .and_then(|v| v.strip_suffix(')'))
+                .map(split_top_level)
+        };
+        if let Some(args) = function("linear-gradient") {
+            let (angle, stops) = match parse_angle(args[0]) {
+                Some(angle) => (angle, &args[1..]),
+                // Like CSS, the default is top to bottom
+                None => (180.0, &args[..]),
+            };
+            Some(Background::LinearGradient {
+                angle,
+                stops: parse_color_stops(stops)?,
+            })
+        } else if let Some(args) = function("radial-gradient") {
+            Some(Background::RadialGradient {
+                stops: parse_color_stops(&args)?,
+            })
+        } else if let Some(args) = function("conic-gradient") {
+            let (start_angle, stops) = match args[0].strip_prefix("from ").and_then(parse_angle) {
+                Some(angle) => (angle, &args[1..]),
+                None => (0.0, &args[..]),
+            };
+            Some(Background::SweepGradient {
+                start_angle,
+                stops: parse_color_stops(stops)?,
+            })
+        } else {
+            Color::parse_style_value(value).map(Background::Solid)
+        }
+    }
+}
+
+/// Layers separated by commas, or `none`
+impl ParseStyleValue for Vec<Background> {
+    fn parse_style_value(value: &str) -> Option<Self> {
+        if value == "none" {
+            return Some(Vec::new());
+        }
+        split_top_level(value)
+            .into_iter()
+            .map(Background::parse_style_value)
+            .collect()
+    }
+}
+
+impl ParseStyleValue for Weight {
+    fn parse_style_value(value: &str) -> Option<Self> {
+        match value {
+            "normal" => Some(Weight::NORMAL),
+            "bold" => Some(Weight::BOLD),
+            _ => value.parse().ok().map(Weight),
+        }
+    }
+}
+
+macro_rules! parse_keywords {
+    ($typ:ty { $($keyword:literal => $val:expr),* $(,)? }) => {
+        impl ParseStyleValue for $typ {
+            fn parse_style_value(value: &str) -> Option<Self> {
+                match value {
+                    $($keyword => Some($val),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+parse_keywords!(Display {
+    "flex" => Display::Flex,
+    "none" => Display::None,
+});
+
+parse_keywords!(Position {
+    "relative" => Position::Relative,
+    "absolute" => Position::Absolute,
+});
+
+parse_keywords!(CursorStyle {
+    "default" => CursorStyle::Default,
+    "pointer" => CursorStyle::Pointer,
+    "text" => CursorStyle::Text,
+    "grab" => CursorStyle::Grab,
+    "grabbing" => CursorStyle::Grabbing,
+    "col-resize" => CursorStyle::ColResize,
+    "row-resize" => CursorStyle::RowResize,
+    "not-allowed" => CursorStyle::NotAllowed,
+    "crosshair" => CursorStyle::Crosshair,
+});
+
+parse_keywords!(Overflow {
+    "visible" => Overflow::Visible,
+    "hidden" => Overflow::Hidden,
+    "scroll" => Overflow::Scroll,
+    "auto" => Overflow::Auto,
+});
+
+parse_keywords!(FlexDirection {
+    "row" => FlexDirection::Row,
+    "column" => FlexDirection::Column,
+    "row-reverse" => FlexDirection::RowReverse,
+    "column-reverse" => FlexDirection::ColumnReverse,
+});
+
+parse_keywords!(FlexWrap {
+    "nowrap" => FlexWrap::NoWrap,
+    "wrap" => FlexWrap::Wrap,
+    "wrap-reverse" => FlexWrap::WrapReverse,
+});
+
+parse_keywords!(AlignItems {
+    "start" => AlignItems::Start,
+    "end" => AlignItems::End,
+    "flex-start" => AlignItems::FlexStart,
+    "flex-end" => AlignItems::FlexEnd,
+    "center" => AlignItems::Center,
+    "baseline" => AlignItems::Baseline,
+    "stretch" => AlignItems::Stretch,
+});
+
+parse_keywords!(AlignContent {
+    "start" => AlignContent::Start,
+    "end" => AlignContent::End,
+    "flex-start" => AlignContent::FlexStart,
+    "flex-end" => AlignContent::FlexEnd,
+    "center" => AlignContent::Center,
+    "stretch" => AlignContent::Stretch,
+    "space-between" => AlignContent::SpaceBetween,
+    "space-around" => AlignContent::SpaceAround,
+    "space-evenly" => AlignContent::SpaceEvenly,
+});
+
+parse_keywords!(FontStyle {
+    "normal" => FontStyle::Normal,
+    "italic" => FontStyle::Italic,
+    "oblique" => FontStyle::Oblique,
+});
+
+parse_keywords!(TextAlign {
+    "start" => TextAlign::Start,
+    "left" => TextAlign::Start,
+    "center" => TextAlign::Center,
+    "end" => TextAlign::End,
+    "right" => TextAlign::End,
+    "justify" => TextAlign::Justify,
+});
+
+parse_keywords!(WhiteSpace {
+    "normal" => WhiteSpace::Wrap,
+    "wrap" => WhiteSpace::Wrap,
+    "nowrap" => WhiteSpace::NoWrap,
+    "pre" => WhiteSpace::Pre,
+});
+
+parse_keywords!(TextOverflow {
+    "clip" => TextOverflow::Clip,
+    "ellipsis" => TextOverflow::Ellipsis,
+    "fade" => TextOverflow::Fade,
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PseudoClass {
+    Hover,
+    Focus,
+    Active,
+}
+
+impl PseudoClass {
+    fn matches(self, interact_state: &InteractionState) -> bool {
+        match self {
+            PseudoClass::Hover => interact_state.is_hovered,
+            PseudoClass::Focus => interact_state.is_focused,
+            PseudoClass::Active => interact_state.is_active,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    class: String,
+    pseudo_class: Option<PseudoClass>,
+    style: Style,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StyleSheetError {
+    /// The stylesheet file couldn't be read
+    Io(String),
+    /// The text isn't a valid stylesheet
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for StyleSheetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StyleSheetError::Io(message) => write!(f, "couldn't read stylesheet: {message}"),
+            StyleSheetError::Parse { line, message } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for StyleSheetError {}
+
+/// A list of style rules for classes of views
+#[derive(Debug, Clone, Default)]
+pub struct StyleSheet {
+    rules: Vec<Rule>,
+}
+
+impl StyleSheet {
+    pub fn parse(text: &str) -> Result<Self, StyleSheetError> {
+        let text = strip_comments(text);
+        let error = |offset: usize, message: String| StyleSheetError::Parse {
+            line: text[..offset].matches('\n').count() + 1,
+            message,
+        };
+        // Where the text of a declaration or selector starts, after any white space
+        let start = |offset: usize, s: &str| offset + s.len() - s.trim_start().len();
+
+        let mut rules = Vec::new();
+        let mut offset = 0;
+        while let Some(open) = text[offset..].find('{').map(|i| offset + i) {
+            let close = text[open..]
+                .find('}')
+                .map(|i| open + i)
+                .ok_or_else(|| error(open, "`{` is never closed".to_string()))?;
+
+            let mut style = Style::default();
+            let mut decl_offset = open + 1;
+            for decl in text[open + 1..close].split(';') {
+                if !decl.trim().is_empty() {
+                    let (name, value) = decl.split_once(':').ok_or_else(|| {
+                        error(
+                            start(decl_offset, decl),
+                            format!("expected `name: value`, found `{}`", decl.trim()),
+                        )
+                    })?;
+                    let name = name.trim().replace('-', "_");
+                    style
+                        .set_property(&name, value.trim())
+                        .map_err(|e| error(start(decl_offset, decl), e))?;
+                }
+                decl_offset += decl.len() + 1;
+            }
+
+            let mut selector_offset = offset;
+            for selector in text[offset..open].split(',') {
+                let (class, pseudo_class) = parse_selector(selector.trim())
+                    .map_err(|e| error(start(selector_offset, selector), e))?;
+                rules.push(Rule {
+                    class,
+                    pseudo_class,
+                    style: style.clone(),
+                });
+                selector_offset += selector.len() + 1;
+            }
+
+            offset = close + 1;
+        }
+        if !text[offset..].trim().is_empty() {
+            return Err(error(offset, "expected `{` after the selector".to_string()));
+        }
+
+        Ok(Self { rules })
+    }
+
+    /// The style from the rules matching a view with `classes`, which the view's own style is
+    /// applied on top of.
+    pub(crate) fn style(&self, classes: &[String], interact_state: &InteractionState) -> Style {
+        if classes.is_empty() {
+            return Style::base();
+        }
+        let matching = |rule: &&Rule| classes.contains(&rule.class);
+        let plain = self
+            .rules
+            .iter()
+            .filter(matching)
+            .filter(|rule| rule.pseudo_class.is_none());
+        let interactive = self.rules.iter().filter(matching).filter(|rule| {
+            rule.pseudo_class
+                .map(|p| p.matches(interact_state))
+                .unwrap_or(false)
+        });
+        Style::base().apply_overriding_styles(plain.chain(interactive).map(|r| r.style.clone()))
+    }
+}
+
+fn parse_selector(selector: &str) -> Result<(String, Option<PseudoClass>), String> {
+    let (class, pseudo_class) = match selector.split_once(':') {
+        Some((class, pseudo_class)) => (class, Some(pseudo_class)),
+        None => (selector, None),
+    };
+    let class = class
+        .strip_prefix('.')
+        .filter(|class| {
+            !class.is_empty()
+                && class
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        })
+        .ok_or_else(|| format!("expected a class selector like `.name`, found `{selector}`"))?;
+    let pseudo_class = match pseudo_class {
+        None => None,
+        Some("hover") => Some(PseudoClass::Hover),
+        Some("focus") => Some(PseudoClass::Focus),
+        Some("active") => Some(PseudoClass::Active),
+        Some(other) => return Err(format!("unknown pseudo class `:{other}`")),
+    };
+    Ok((class.to_string(), pseudo_class))
+}
+
+/// Replace `/* */` comments with spaces, keeping the newlines so errors report the right line
+fn strip_comments(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("/*") {
+        result.push_str(&rest[..start]);
+        let end = rest[start + 2..]
+            .find("*/")
+            .map(|i| start + 2 + i + 2)
+            .unwrap_or(rest.len());
+        result.extend(
+            rest[start..end]
+                .chars()
+                .map(|c| if c == '\n' { '\n' } else { ' ' }),
+        );
+        rest = &rest[end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Load the stylesheet at `path`, and reload it whenever the file is modified, which is checked on
+/// a background thread. The thread stops when the scope of `cx` is disposed.
+///
+/// When the file can't be read or parsed, the previous stylesheet stays in use and the returned
+/// signal holds the error, until a later version of the file loads.
+pub fn watch_stylesheet(
+    cx: AppContext,
+    path: impl Into<PathBuf>,
+) -> ReadSignal<Option<StyleSheetError>> {
+    let path = path.into();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let stop = Arc::new(AtomicBool::new(false));
+    {
+        let stop = stop.clone();
+        on_cleanup(cx.scope, move || stop.store(true, Ordering::Relaxed));
+    }
+    std::thread::spawn(move || {
+        let mut last_modified: Option<Result<SystemTime, String>> = None;
+        while !stop.load(Ordering::Relaxed) {
+            let modified = std::fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .map_err(|e| e.to_string());
+            if last_modified.as_ref() != Some(&modified) {
+                last_modified = Some(modified.clone());
+                let text = modified
+                    .and_then(|_| std::fs::read_to_string(&path).map_err(|e| e.to_string()));
+                if tx.send(text).is_err() {
+                    return;
+                }
+            }
+            std::thread::sleep(WATCH_INTERVAL);
+        }
+    });
+
+    let text = create_signal_from_channel(cx, rx);
+    let (error, set_error) = create_signal(cx.scope, None);
+    create_effect(cx.scope, move |_| {
+        if let Some(text) = text.get() {
+            match text
+                .map_err(StyleSheetError::Io)
+                .and_then(|text| StyleSheet::parse(&text))
+            {
+                Ok(stylesheet) => {
+                    AppContext::update_stylesheet(stylesheet);
+                    set_error.set(None);
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        }
+    });
+    error
+}
+
+#[cfg(test)]
+mod tests {
+    use vello::peniko::Color;
+
+    use super::{ParseStyleValue, StyleSheet, StyleSheetError};
+    use crate::{
+        context::InteractionState,
+        style::{Background, Length, StyleValue},
+    };
+
+    #[test]
+    fn parse_stylesheet() {
+        let sheet = StyleSheet::parse(
+            "
+            /* buttons */
+            .button { padding-left: 8px; border_color: #ff0000; }
+            .button:hover, .other { padding_left: 50%; }
+            ",
+        )
+        .unwrap();
+
+        let classes = vec!["button".to_string()];
+        let style = sheet.style(&classes, &InteractionState::default());
+        assert_eq!(style.padding_left, StyleValue::Val(Length::Pt(8.0)));
+        assert_eq!(
+            style.border_color,
+            StyleValue::Val(Color::rgba8(255, 0, 0, 255))
+        );
+        assert_eq!(style.padding_right, StyleValue::Base);
+
+        let hovered = InteractionState {
+            is_hovered: true,
+            ..Default::default()
+        };
+        let style = sheet.style(&classes, &hovered);
+        assert_eq!(style.padding_left, StyleValue::Val(Length::Pct(0.5)));
+
+        assert_eq!(
+            Vec::<Background>::parse_style_value("linear-gradient(90deg, #000, #fff 80%), #f00"),
+            Some(vec![
+                Background::LinearGradient {
+                    angle: 90.0,
+                    stops: vec![
+                        (0.0, Color::rgba8(0, 0, 0, 255)),
+                        (0.8, Color::rgba8(255, 255, 255, 255)),
+                    ],
+                },
+                Background::Solid(Color::rgba8(255, 0, 0, 255)),
+            ])
+        );
+
+        assert_eq!(
+            StyleSheet::parse(".a {\n  padding: 8px;\n}").unwrap_err(),
+            StyleSheetError::Parse {
+                line: 2,
+                message: "unknown property `padding`".to_string(),
+            }
+        );
+    }
+}
@@ -1 +1,140 @@
+//! # Text spacing
+//! cosmic-text lays out text without any spacing between the letters, and it doesn't align or
+//! justify the lines. Text with a `letter_spacing` or `text_align` style is drawn with a
+//! [`TextSpacing`] that moves the glyphs, see
+//! [`Renderer::draw_text_with_spacing`](crate::Renderer::draw_text_with_spacing), and it's
+//! measured and hit-tested here with the letter spacing added after every glyph in the same
+//! way.
 
+use floem_renderer::{Renderer, TextSpacing};
+use glazier::kurbo::Point;
+
+use crate::{
+    context::PaintCx,
+    cosmic_text::{LayoutGlyph, LayoutRun, TextLayout},
+    style::TextAlign,
+};
+
+/// Draw `layout` at `point` with `spacing`
+pub(crate) fn draw_text(
+    cx: &mut PaintCx,
+    layout: &TextLayout,
+    point: Point,
+    spacing: &TextSpacing,
+) {
+    if *spacing == TextSpacing::default() {
+        cx.draw_text(layout, point);
+    } else {
+        cx.draw_text_with_spacing(layout, point, spacing);
+    }
+}
+
+/// The spacing to draw `layout` with in a box that's `width` wide. Each line is aligned by
+/// `text_align`, and justified lines, other than the last line of a paragraph, get the width
+/// they don't fill spread over their spaces.
+pub(crate) fn text_spacing(
+    layout: &TextLayout,
+    width: f32,
+    letter_spacing: Option<f32>,
+    text_align: TextAlign,
+) -> TextSpacing {
+    let letter_spacing = letter_spacing.unwrap_or(0.0);
+    let mut spacing = TextSpacing {
+        letter_spacing: letter_spacing as f64,
+        ..Default::default()
+    };
+    if text_align == TextAlign::Start {
+        return spacing;
+    }
+
+    let runs: Vec<LayoutRun> = layout.layout_runs().collect();
+    for (i, run) in runs.iter().enumerate() {
+        // The spaces at the end of a wrapped line don't take up any width of the box
+        let end = run
+            .glyphs
+            .iter()
+            .rposition(|glyph| !is_space(run, glyph))
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let glyphs = &run.glyphs[..end];
+        let free_width = (width - line_width(glyphs, letter_spacing)).max(0.0) as f64;
+        let last_line = runs
+            .get(i + 1)
+            .map(|next| next.line_i != run.line_i)
+            .unwrap_or(true);
+        let (offset, word_spacing) = match text_align {
+            TextAlign::Start => (0.0, 0.0),
+            TextAlign::Center => (free_width / 2.0, 0.0),
+            TextAlign::End => (free_width, 0.0),
+            TextAlign::Justify if last_line => (0.0, 0.0),
+            TextAlign::Justify => {
+                let spaces = glyphs.iter().filter(|glyph| is_space(run, glyph)).count();
+                if spaces > 0 {
+                    (0.0, free_width / spaces as f64)
+                } else {
+                    (0.0, 0.0)
+                }
+            }
+        };
+        spacing.line_offsets.push(offset);
+        spacing.word_spacings.push(word_spacing);
+    }
+    spacing
+}
+
+/// Whether `glyph` of `run` is white space, which justified text stretches
+fn is_space(run: &LayoutRun, glyph: &LayoutGlyph) -> bool {
+    run.text
+        .get(glyph.start..glyph.end)
+        .map(|text| !text.is_empty() && text.chars().all(char::is_whitespace))
+        .unwrap_or(false)
+}
+
+/// The width of a line of `glyphs` with `letter_spacing` added after every glyph
+fn line_width(glyphs: &[LayoutGlyph], letter_spacing: f32) -> f32 {
+    let width = glyphs
+        .iter()
+        .map(|glyph| glyph.x + glyph.w)
+        .fold(0.0, f32::max);
+    width + letter_spacing * glyphs.len() as f32
+}
+
+/// The width of the widest line of `layout` with `letter_spacing` added after every glyph
+pub(crate) fn letter_spaced_width(layout: &TextLayout, letter_spacing: f32) -> f32 {
+    layout
+        .layout_runs()
+        .map(|run| line_width(run.glyphs, letter_spacing))
+        .fold(0.0, f32::max)
+}
+
+/// The byte index of the text on the first line of `layout` that comes before the glyph that
+/// doesn't fit in `width` anymore with `letter_spacing` added
+pub(crate) fn letter_spaced_index(layout: &TextLayout, width: f32, letter_spacing: f32) -> usize {
+    layout
+        .layout_runs()
+        .next()
+        .map(|run| {
+            run.glyphs
+                .iter()
+                .enumerate()
+                .find(|(i, glyph)| glyph.x + glyph.w + letter_spacing * *i as f32 > width)
+                .map(|(_, glyph)| glyph.start)
+                .unwrap_or(run.text.len())
+        })
+        .unwrap_or(0)
+}
+
+/// Wrap `layout` so its lines fit in `width` with `letter_spacing` added. The text is wrapped
+/// without knowing about the spacing, so it's wrapped narrower until the spacing fits.
+pub(crate) fn wrap_letter_spaced(layout: &mut TextLayout, width: f32, letter_spacing: f32) {
+    let mut wrap_width = width;
+    // A few rounds are enough, as every round takes the overflow of the widest line off
+    for _ in 0..4 {
+        layout.set_size(wrap_width, f32::MAX);
+        let overflow = letter_spaced_width(layout, letter_spacing) - width;
+        if overflow <= 0.0 || wrap_width <= 0.0 {
+            break;
+        }
+        wrap_width = (wrap_width - overflow).max(0.0);
+    }
+}
@@ -0,0 +1,123 @@
+//! # Timers
+//! [`AppContext::request_timer`](crate::app::AppContext::request_timer) runs a callback on the UI
+//! thread once a duration has passed, through the timers of the window. The callback can update
+//! signals and views like an event handler.
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+};
+
+use leptos_reactive::{on_cleanup, Scope};
+
+use crate::{app::AppContext, id::Id};
+
+thread_local! {
+    static SCOPE_TIMERS: RefCell<ScopeTimers> = Default::default();
+}
+
+/// The timers of each scope that haven't run or been cancelled yet
+#[derive(Default)]
+struct ScopeTimers {
+    tokens: HashMap<Scope, HashSet<TimerToken>>,
+    scopes: HashMap<TimerToken, Scope>,
+}
+
+pub type TimerCallback = dyn FnOnce();
+
+/// Identifies a requested timer, so it can be cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerToken(Id);
+
+impl TimerToken {
+    pub(crate) fn next() -> Self {
+        Self(Id::next())
+    }
+
+    /// Keep the callback of the timer from running, if it hasn't already
+    pub fn cancel(self) {
+        AppContext::cancel_timer(self);
+    }
+}
+
+/// Cancel the timer when `scope` is disposed. The scope cancels all its timers that are left
+/// with a single cleanup.
+pub(crate) fn add_scope_timer(scope: Scope, token: TimerToken) {
+    let first = SCOPE_TIMERS.with(|timers| {
+        let mut timers = timers.borrow_mut();
+        timers.scopes.insert(token, scope);
+        let first = !timers.tokens.contains_key(&scope);
+        timers.tokens.entry(scope).or_default().insert(token);
+        first
+    });
+    if first {
+        on_cleanup(scope, move || {
+            let tokens = SCOPE_TIMERS.with(|timers| {
+                let mut timers = timers.borrow_mut();
+                let tokens = timers.tokens.remove(&scope).unwrap_or_default();
+                for token in &tokens {
+                    timers.scopes.remove(token);
+                }
+                tokens
+            });
+            for token in tokens {
+                token.cancel();
+            }
+        });
+    }
+}
+
+/// Forget the timer once it ran or was cancelled
+pub(crate) fn remove_scope_timer(token: TimerToken) {
+    SCOPE_TIMERS.with(|timers| {
+        let mut timers = timers.borrow_mut();
+        if let Some(scope) = timers.scopes.remove(&token) {
+            if let Some(tokens) = timers.tokens.get_mut(&scope) {
+                tokens.remove(&token);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use leptos_reactive::{create_runtime, create_scope};
+
+    use super::{add_scope_timer, remove_scope_timer, TimerToken, SCOPE_TIMERS};
+    use crate::app::{UpdateMessage, UPDATE_MESSAGES};
+
+    #[test]
+    fn disposed_scope_cancels_its_timers() {
+        let (first, second, ran) = (TimerToken::next(), TimerToken::next(), TimerToken::next());
+        let disposer = create_scope(create_runtime(), move |cx| {
+            add_scope_timer(cx, first);
+            add_scope_timer(cx, second);
+            add_scope_timer(cx, ran);
+        });
+        // A timer that already ran isn't cancelled again
+        remove_scope_timer(ran);
+        disposer.dispose();
+
+        let cancelled: Vec<TimerToken> = UPDATE_MESSAGES.with(|msgs| {
+            msgs.borrow_mut()
+                .drain(..)
+                .filter_map(|msg| match msg {
+                    UpdateMessage::CancelTimer(token) => Some(token),
+                    _ => None,
+                })
+                .collect()
+        });
+        assert_eq!(cancelled.len(), 2);
+        assert_eq!(
+            cancelled.into_iter().collect::<HashSet<_>>(),
+            HashSet::from([first, second])
+        );
+        SCOPE_TIMERS.with(|timers| {
+            let timers = timers.borrow();
+            assert!(timers.tokens.is_empty());
+            assert!(timers.scopes.is_empty());
+        });
+    }
+}
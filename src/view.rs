@@ -2,15 +2,20 @@ use std::any::Any;
 
 use bitflags::bitflags;
 use floem_renderer::Renderer;
-use glazier::kurbo::{Line, Point, Size};
-use taffy::{prelude::Node, style::Display};
+use glazier::kurbo::{self, Affine, BezPath, Line, Point, Shape, Size, Vec2};
+use taffy::{
+    prelude::{Node, Rect},
+    style::Display,
+};
 
 use crate::{
     context::{EventCx, LayoutCx, PaintCx, UpdateCx},
-    event::Event,
+    event::{Event, EventPhase},
+    gesture::gesture_event,
     id::Id,
-    style::ReifiedStyle,
+    style::{Background, BackgroundImage, Overflow, ReifiedStyle, DEFAULT_FONT_SIZE},
 };
+use vello::peniko::{Color, Extend, Gradient};
 
 bitflags! {
     #[derive(Default)]
@@ -55,9 +60,9 @@ pub trait View {
     fn layout_main(&mut self, cx: &mut LayoutCx) -> Node {
         cx.save();
 
-        // TODO: We only need to get the view style if the cached reified style is None.
-        let view_style = self.view_style().unwrap_or_default();
-        let style = cx.get_reified_style(&view_style, self.id()).cloned();
+        let style = cx
+            .get_reified_style(|| self.view_style().unwrap_or_default(), self.id())
+            .cloned();
         if let Some(style) = style {
             if style.font_size.is_some() {
                 cx.font_size = style.font_size;
@@ -71,6 +76,33 @@ pub trait View {
             if style.font_style.is_some() {
                 cx.font_style = style.font_style;
             }
+            if style.line_height.is_some() {
+                cx.line_height = style.line_height;
+            }
+            if style.letter_spacing.is_some() {
+                cx.letter_spacing = style.letter_spacing;
+            }
+            if style.text_align.is_some() {
+                cx.text_align = style.text_align;
+            }
+            if style.white_space.is_some() {
+                cx.white_space = style.white_space;
+            }
+            if style.text_overflow.is_some() {
+                cx.text_overflow = style.text_overflow;
+            }
+        }
+
+        if cx.root_font_size.is_none() {
+            // The root is laid out first, and `Rem` lengths are relative to its font size
+            cx.root_font_size = Some(cx.font_size.unwrap_or(DEFAULT_FONT_SIZE));
+        }
+        let length_context = cx.length_context();
+        let view = cx.app_state.view_state(self.id());
+        if view.length_context != Some(length_context) {
+            // The relative lengths in the style need to be resolved again
+            view.length_context = Some(length_context);
+            view.request_layout = true;
         }
 
         let node = self.layout(cx);
@@ -123,6 +155,7 @@ pub trait View {
         let viewport = cx.viewport.unwrap_or_default();
         let window_origin = origin + cx.window_origin.to_vec2() + viewport.origin().to_vec2();
         cx.window_origin = window_origin;
+        cx.app_state.view_state(self.id()).window_origin = window_origin;
 
         if let Some(resize) = cx.get_resize_listener(self.id()) {
             let new_rect = size.to_rect().with_origin(origin);
@@ -133,6 +166,15 @@ pub trait View {
             }
         }
 
+        let scroll_offset = cx.app_state.view_state(self.id()).scroll_offset;
+        if scroll_offset != Vec2::ZERO {
+            // Keep the offset within the content, which might have shrunk
+            cx.app_state.set_scroll_offset(self.id(), scroll_offset);
+            let scroll_offset = cx.app_state.view_state(self.id()).scroll_offset;
+            cx.window_origin -= scroll_offset;
+            cx.viewport = Some(cx.viewport.unwrap_or_else(|| size.to_rect()) + scroll_offset);
+        }
+
         self.compute_layout(cx);
 
         cx.restore();
@@ -140,6 +182,10 @@ pub trait View {
 
     fn compute_layout(&mut self, cx: &mut LayoutCx);
 
+    /// Send the event to the view: its capture listeners run, then the event goes on to the
+    /// child on `id_path` or is handled by [`View::event`], and then the bubble listeners run.
+    /// Returns true when the event was handled or its propagation stopped, so no other view
+    /// should get it.
     fn event_main(&mut self, cx: &mut EventCx, id_path: Option<&[Id]>, event: Event) -> bool {
         let id = self.id();
         if cx.app_state.is_hidden(id) {
@@ -147,55 +193,78 @@ pub trait View {
         }
 
         let event = cx.offset_event(self.id(), event);
+        // Children are positioned in the content, which may be scrolled
+        let scroll_offset = cx.app_state.view_state(id).scroll_offset;
+
+        // The rest of the path when the event is for a view inside this one
+        let mut child_path = None;
         if let Some(id_path) = id_path {
-            let id = id_path[0];
-            let id_path = &id_path[1..];
-            if id == self.id() && !id_path.is_empty() {
-                if let Some(child) = self.child(id_path[0]) {
-                    return child.event_main(cx, Some(id_path), event);
-                }
+            let (id, id_path) = match id_path.split_first() {
+                Some(split) => split,
+                // The event is for the view that passed it down
+                None => return false,
+            };
+            if *id == self.id() && !id_path.is_empty() {
+                child_path = Some(id_path);
             }
         }
 
-        if let Some(listener) = event.listener() {
-            if let Some(listeners) = cx.get_event_listener(self.id()) {
-                if let Some(action) = listeners.get(&listener) {
-                    if (*action)(&event) {
-                        return true;
-                    }
-                }
+        let pointer_pos = match &event {
+            Event::MouseMove(event) => Some(event.pos),
+            _ => None,
+        };
+        if pointer_pos.is_some() || matches!(event, Event::WindowMouseLeave) {
+            let rect = cx.get_size(self.id()).unwrap_or_default().to_rect();
+            let is_in_hovered = cx.app_state.hovered.contains(&self.id());
+            let contains = pointer_pos.map(|pos| rect.contains(pos)).unwrap_or(false);
+
+            if contains && !is_in_hovered {
+                cx.app_state
+                    .set_hovered(self.id(), true, || self.view_style().unwrap_or_default());
+                cx.run_own_listeners(self.id(), &Event::PointerEnter);
+            } else if !contains && is_in_hovered {
+                cx.app_state
+                    .set_hovered(self.id(), false, || self.view_style().unwrap_or_default());
+                cx.run_own_listeners(self.id(), &Event::PointerLeave);
             }
         }
 
-        match &event {
-            Event::MouseMove(event) => {
-                let rect = cx.get_size(self.id()).unwrap_or_default().to_rect();
-                let is_in_hovered = cx.app_state.hovered.contains(&self.id());
-
-                if rect.contains(event.pos) && !is_in_hovered {
-                    cx.app_state.hovered.insert(self.id());
-                    cx.app_state.reset_children_layout(self.id());
-                } else if !rect.contains(event.pos) && is_in_hovered {
-                    cx.app_state.hovered.remove(&self.id());
-                    cx.app_state.reset_children_layout(self.id());
-                }
-            }
-            _ => {}
+        if cx.run_listener(id, EventPhase::Capture, &event) {
+            return true;
         }
 
-        if self.event(cx, id_path, event) {
+        let wheel_delta = match &event {
+            Event::MouseWheel(event) => Some(event.wheel_delta),
+            _ => None,
+        };
+        let content_event = event.clone().offset((-scroll_offset.x, -scroll_offset.y));
+        let child = child_path.and_then(|path| self.child(path[0]).map(|child| (child, path)));
+        let handled = if let Some((child, path)) = child {
+            child.event_main(cx, Some(path), content_event)
+        } else if self.event(cx, id_path, content_event) {
+            true
+        } else if let Some(delta) = wheel_delta.filter(|_| !cx.default_prevented) {
+            // Scroll the overflow when none of the children used the wheel
+            cx.app_state.set_scroll_offset(id, scroll_offset + delta)
+        } else {
+            false
+        };
+
+        if cx.propagation_stopped {
             return true;
         }
-
-        false
+        let handled = cx.handle_drag(id, &event) || handled;
+        let handled = gesture_event(cx, id, &event) || handled;
+        cx.run_listener(id, EventPhase::Bubble, &event) || handled
     }
 
     fn event(&mut self, cx: &mut EventCx, id_path: Option<&[Id]>, event: Event) -> bool;
 
     fn paint_main(&mut self, cx: &mut PaintCx) {
         let id = self.id();
-        let view_style = self.view_style().unwrap_or_default();
-        let style = cx.get_reified_style(&view_style, id).cloned();
+        let style = cx
+            .get_reified_style(|| self.view_style().unwrap_or_default(), id)
+            .cloned();
         if style
             .as_ref()
             .map(|s| s.display == Display::None)
@@ -229,10 +298,46 @@ pub trait View {
                 if style.font_style.is_some() {
                     cx.font_style = style.font_style;
                 }
+                if style.line_height.is_some() {
+                    cx.line_height = style.line_height;
+                }
+                if style.letter_spacing.is_some() {
+                    cx.letter_spacing = style.letter_spacing;
+                }
+                if style.text_align.is_some() {
+                    cx.text_align = style.text_align;
+                }
+                if style.white_space.is_some() {
+                    cx.white_space = style.white_space;
+                }
+                if style.text_overflow.is_some() {
+                    cx.text_overflow = style.text_overflow;
+                }
+            }
+            let clips = style
+                .as_ref()
+                .map(|style| style.overflow_x.is_clipped() || style.overflow_y.is_clipped())
+                .unwrap_or(false);
+            let saved_clip = cx.clip;
+            if clips {
+                let rect = size.to_rect();
+                cx.clip(&saved_clip.map(|clip| clip.intersect(rect)).unwrap_or(rect));
+            }
+            let scroll_offset = cx.app_state.view_state(id).scroll_offset;
+            if scroll_offset != Vec2::ZERO {
+                cx.offset((-scroll_offset.x, -scroll_offset.y));
             }
             self.paint(cx);
+            if scroll_offset != Vec2::ZERO {
+                cx.offset((scroll_offset.x, scroll_offset.y));
+            }
+            if clips {
+                cx.set_clip(saved_clip);
+            }
             if let Some(style) = style.as_ref() {
-                paint_border(cx, style, size);
+                paint_scroll_bars(cx, style, id, size);
+                let border = cx.get_border(id);
+                paint_border(cx, style, border, size);
             }
         }
         cx.restore();
@@ -242,46 +347,177 @@ pub trait View {
 }
 
 fn paint_bg(cx: &mut PaintCx, style: &ReifiedStyle, size: Size) {
-    let bg = match style.background {
-        Some(color) => color,
-        None => return,
+    let radius = style.border_radius as f64;
+    let shape = if radius > 0.0 {
+        size.to_rect().to_rounded_rect(radius).into_path(0.1)
+    } else {
+        size.to_rect().into_path(0.1)
     };
+    // The first layer is on top, so it is painted last
+    for background in style.background.iter().rev() {
+        match background {
+            Background::Solid(color) => cx.fill(&shape, *color),
+            Background::LinearGradient { angle, stops } => {
+                let angle = angle.to_radians();
+                let direction = Vec2::new(angle.sin(), -angle.cos());
+                let length = (size.width * angle.sin()).abs() + (size.height * angle.cos()).abs();
+                let center = size.to_rect().center();
+                let gradient = Gradient::new_linear(
+                    center - direction * length / 2.0,
+                    center + direction * length / 2.0,
+                )
+                .with_stops(stops.as_slice());
+                cx.fill(&shape, &gradient);
+            }
+            Background::RadialGradient { stops } => {
+                let radius = size.to_vec2().hypot() / 2.0;
+                let gradient = Gradient::new_radial(size.to_rect().center(), radius as f32)
+                    .with_stops(stops.as_slice());
+                cx.fill(&shape, &gradient);
+            }
+            Background::SweepGradient { start_angle, stops } => {
+                // Angles are from the x axis, which is a quarter turn from the top
+                let start = (start_angle - 90.0).to_radians();
+                let gradient = Gradient::new_sweep(
+                    size.to_rect().center(),
+                    start as f32,
+                    (start + std::f64::consts::TAU) as f32,
+                )
+                .with_stops(stops.as_slice());
+                cx.fill(&shape, &gradient);
+            }
+            Background::Image(image) => paint_bg_image(cx, image, &shape, size),
+        }
+    }
+}
 
-    let radius = style.border_radius;
-    if radius > 0.0 {
-        let rect = size.to_rect().to_rounded_rect(radius as f64);
-        cx.fill(&rect, bg);
+fn paint_bg_image(cx: &mut PaintCx, image: &BackgroundImage, shape: &BezPath, size: Size) {
+    let image_size = Size::new(image.image.width as f64, image.image.height as f64);
+    if image_size.is_empty() {
+        return;
+    }
+    let (sx, sy) = image.size.scale(image_size, size);
+    if sx <= 0.0 || sy <= 0.0 {
+        return;
+    }
+    // The image brush isn't transformed by itself, so the image is scaled by scaling the
+    // transform and drawing the shape in image space
+    let scale = Affine::scale_non_uniform(sx, sy);
+    let shape = if image.repeat {
+        scale.inverse() * shape.clone()
+    } else {
+        let rect = image_size
+            .to_rect()
+            .intersect(scale.inverse().transform_rect_bbox(size.to_rect()));
+        rect.into_path(0.1)
+    };
+    let brush = image.image.clone().with_extend(if image.repeat {
+        Extend::Repeat
     } else {
-        cx.fill(&size.to_rect(), bg);
+        Extend::Pad
+    });
+    let transform = cx.transform;
+    cx.paint_state
+        .renderer
+        .as_mut()
+        .unwrap()
+        .transform(transform * scale);
+    cx.fill(&shape, &brush);
+    cx.paint_state
+        .renderer
+        .as_mut()
+        .unwrap()
+        .transform(transform);
+}
+
+/// Views with scrollable overflow show how far their content is scrolled
+fn paint_scroll_bars(cx: &mut PaintCx, style: &ReifiedStyle, id: Id, size: Size) {
+    const BAR_WIDTH: f64 = 6.0;
+    const BAR_PAD: f64 = 2.0;
+    const BAR_MIN_LENGTH: f64 = 10.0;
+
+    if !style.overflow_x.is_scrollable() && !style.overflow_y.is_scrollable() {
+        return;
+    }
+
+    let content_size = cx.app_state.content_size(id);
+    let scroll_offset = cx.app_state.view_state(id).scroll_offset;
+    let color = Color::BLACK.with_alpha_factor(0.7);
+    // The start and end of a bar along a track of `size`
+    let bar = |size: f64, content_size: f64, offset: f64| {
+        let length = (size * size / content_size).min(size).max(BAR_MIN_LENGTH);
+        let scrolled = if content_size > size {
+            offset / (content_size - size)
+        } else {
+            0.0
+        };
+        let start = (size - length) * scrolled;
+        (start, start + length)
+    };
+
+    let shows_bar = |overflow: Overflow, size: f64, content_size: f64| {
+        overflow == Overflow::Scroll || (overflow == Overflow::Auto && content_size > size)
+    };
+    if shows_bar(style.overflow_y, size.height, content_size.height) {
+        let (y0, y1) = bar(size.height, content_size.height, scroll_offset.y);
+        let rect = kurbo::Rect::new(
+            size.width - BAR_WIDTH - BAR_PAD,
+            y0,
+            size.width - BAR_PAD,
+            y1,
+        );
+        cx.fill(&rect.to_rounded_rect(BAR_WIDTH / 2.0), color);
+    }
+    if shows_bar(style.overflow_x, size.width, content_size.width) {
+        let (x0, x1) = bar(size.width, content_size.width, scroll_offset.x);
+        let rect = kurbo::Rect::new(
+            x0,
+            size.height - BAR_WIDTH - BAR_PAD,
+            x1,
+            size.height - BAR_PAD,
+        );
+        cx.fill(&rect.to_rounded_rect(BAR_WIDTH / 2.0), color);
     }
 }
 
-fn paint_border(cx: &mut PaintCx, style: &ReifiedStyle, size: Size) {
-    let left = style.border_left;
-    let top = style.border_top;
-    let right = style.border_right;
-    let bottom = style.border_bottom;
+fn paint_border(cx: &mut PaintCx, style: &ReifiedStyle, border: Rect<f32>, size: Size) {
+    let left = border.left;
+    let top = border.top;
+    let right = border.right;
+    let bottom = border.bottom;
 
     let border_color = style.border_color;
-    if left == top && top == right && right == bottom && bottom == left && left > 0.0 {
+    let left_color = style.border_left_color.unwrap_or(border_color);
+    let top_color = style.border_top_color.unwrap_or(border_color);
+    let right_color = style.border_right_color.unwrap_or(border_color);
+    let bottom_color = style.border_bottom_color.unwrap_or(border_color);
+    if left == top
+        && top == right
+        && right == bottom
+        && bottom == left
+        && left > 0.0
+        && left_color == top_color
+        && top_color == right_color
+        && right_color == bottom_color
+    {
         let half = left as f64 / 2.0;
         let rect = size.to_rect().inflate(-half, -half);
         let radius = style.border_radius;
         if radius > 0.0 {
             cx.stroke(
                 &rect.to_rounded_rect(radius as f64),
-                border_color,
+                left_color,
                 left as f64,
             );
         } else {
-            cx.stroke(&rect, border_color, left as f64);
+            cx.stroke(&rect, left_color, left as f64);
         }
     } else {
         if left > 0.0 {
             let half = left as f64 / 2.0;
             cx.stroke(
                 &Line::new(Point::new(half, 0.0), Point::new(half, size.height)),
-                border_color,
+                left_color,
                 left as f64,
             );
         }
@@ -292,7 +528,7 @@ fn paint_border(cx: &mut PaintCx, style: &ReifiedStyle, size: Size) {
                     Point::new(size.width - half, 0.0),
                     Point::new(size.width - half, size.height),
                 ),
-                border_color,
+                right_color,
                 right as f64,
             );
         }
@@ -300,7 +536,7 @@ fn paint_border(cx: &mut PaintCx, style: &ReifiedStyle, size: Size) {
             let half = top as f64 / 2.0;
             cx.stroke(
                 &Line::new(Point::new(0.0, half), Point::new(size.width, half)),
-                border_color,
+                top_color,
                 top as f64,
             );
         }
@@ -311,9 +547,32 @@ fn paint_border(cx: &mut PaintCx, style: &ReifiedStyle, size: Size) {
                     Point::new(0.0, size.height - half),
                     Point::new(size.width, size.height - half),
                 ),
-                border_color,
+                bottom_color,
                 bottom as f64,
             );
         }
     }
+
+    paint_outline(cx, style, size);
+}
+
+/// The outline is drawn outside the box, so it never changes the layout
+fn paint_outline(cx: &mut PaintCx, style: &ReifiedStyle, size: Size) {
+    let width = style.outline_width;
+    if width <= 0.0 {
+        return;
+    }
+
+    let outset = (style.outline_offset + width / 2.0) as f64;
+    let rect = size.to_rect().inflate(outset, outset);
+    let radius = style.border_radius;
+    if radius > 0.0 {
+        cx.stroke(
+            &rect.to_rounded_rect(radius as f64 + outset),
+            style.outline_color,
+            width as f64,
+        );
+    } else {
+        cx.stroke(&rect, style.outline_color, width as f64);
+    }
 }
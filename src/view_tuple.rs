@@ -10,6 +10,8 @@ pub trait ViewTuple {
     fn foreach_rev<F: FnMut(&mut dyn View) -> bool>(&mut self, f: &mut F);
 
     fn child(&mut self, id: Id) -> Option<&mut dyn View>;
+
+    fn views_mut(&mut self) -> Vec<&mut dyn View>;
 }
 
 macro_rules! impl_view_tuple {
@@ -29,6 +31,10 @@ macro_rules! impl_view_tuple {
                 None
             }
 
+            fn views_mut(&mut self) -> Vec<&mut dyn View> {
+                vec![$( &mut self.$i as &mut dyn View, )*]
+            }
+
             fn paint(&mut self, cx: &mut PaintCx) {
                 $(
                     self.$i.paint(cx);
@@ -0,0 +1,80 @@
+use glazier::kurbo::Point;
+
+use crate::{
+    app::AppContext,
+    context::{EventCx, LayoutCx, PaintCx, UpdateCx},
+    event::Event,
+    gesture::GestureHandler,
+    id::Id,
+    menu::Menu,
+    view::{ChangeFlags, View},
+};
+
+/// Opens the menu made by `menu` where the child is clicked with the secondary button
+pub struct ContextMenu<V: View> {
+    id: Id,
+    child: V,
+    menu: Box<dyn Fn() -> Menu>,
+    window_origin: Point,
+}
+
+pub fn context_menu<V: View>(
+    cx: AppContext,
+    child: impl FnOnce(AppContext) -> V,
+    menu: impl Fn() -> Menu + 'static,
+) -> ContextMenu<V> {
+    let id = cx.new_id();
+    let mut child_cx = cx;
+    child_cx.id = id;
+    let child = child(child_cx);
+    AppContext::update_gesture(
+        id,
+        GestureHandler::SecondaryClick(Box::new(move |pos| {
+            AppContext::update_state(id, pos, false);
+        })),
+    );
+    ContextMenu {
+        id,
+        child,
+        menu: Box::new(menu),
+        window_origin: Point::ZERO,
+    }
+}
+
+impl<V: View> View for ContextMenu<V> {
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn child(&mut self, id: Id) -> Option<&mut dyn View> {
+        if self.child.id() == id {
+            Some(&mut self.child)
+        } else {
+            None
+        }
+    }
+
+    fn update(&mut self, _cx: &mut UpdateCx, state: Box<dyn std::any::Any>) -> ChangeFlags {
+        if let Ok(pos) = state.downcast::<Point>() {
+            AppContext::show_context_menu((self.menu)(), self.window_origin + pos.to_vec2());
+        }
+        ChangeFlags::empty()
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx) -> taffy::prelude::Node {
+        cx.layout_node(self.id, true, |cx| vec![self.child.layout_main(cx)])
+    }
+
+    fn compute_layout(&mut self, cx: &mut LayoutCx) {
+        self.window_origin = cx.window_origin;
+        self.child.compute_layout_main(cx);
+    }
+
+    fn event(&mut self, cx: &mut EventCx, id_path: Option<&[Id]>, event: Event) -> bool {
+        self.child.event_main(cx, id_path, event)
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        self.child.paint_main(cx);
+    }
+}
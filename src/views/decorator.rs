@@ -1,10 +1,13 @@
-use glazier::kurbo::{Point, Rect};
-use leptos_reactive::create_effect;
+use glazier::kurbo::{Point, Rect, Vec2};
+use leptos_reactive::{create_effect, on_cleanup};
 
 use crate::{
     app::AppContext,
-    event::{Event, EventListner},
-    style::Style,
+    context::EventCx,
+    event::{Event, EventListner, EventPhase},
+    gesture::{DragGesture, GestureHandler, LONG_PRESS_DURATION},
+    ime::TextInputState,
+    style::{Breakpoint, Style},
     view::View,
 };
 
@@ -27,9 +30,147 @@ pub trait Decorators: View + Sized {
         self
     }
 
+    /// A style that applies on top of `style` only while the window width is in `breakpoint`
+    fn responsive(
+        self,
+        cx: AppContext,
+        breakpoint: Breakpoint,
+        style: impl Fn() -> Style + 'static,
+    ) -> Self {
+        let id = self.id();
+        create_effect(cx.scope, move |_| {
+            let style = style();
+            AppContext::update_responsive_style(id, breakpoint, style);
+        });
+        self
+    }
+
+    /// Tag the view with stylesheet classes, separated by spaces like `"button primary"`
+    fn class(self, class: &str) -> Self {
+        let id = self.id();
+        AppContext::update_classes(id, class.split_whitespace().map(String::from).collect());
+        self
+    }
+
+    /// Let the view be focused with Tab, in tree order
+    fn focusable(self) -> Self {
+        self.tab_index(0)
+    }
+
+    /// Let the view be focused, with a tab order like HTML `tabindex`: views with a positive
+    /// index are focused first, in increasing order, then the ones with 0 in tree order. Views
+    /// with a negative index can only be focused by clicking, the arrow keys or programmatically.
+    fn tab_index(self, tab_index: i32) -> Self {
+        let id = self.id();
+        AppContext::update_tab_index(id, Some(tab_index));
+        self
+    }
+
+    /// Keep Tab navigation within the view while it is shown, like for a dialog
+    fn focus_trap(self) -> Self {
+        let id = self.id();
+        AppContext::update_focus_trap(id);
+        self
+    }
+
+    /// Let the arrow keys move the focus between the focusable views inside, like in a list
+    fn focus_scope(self) -> Self {
+        let id = self.id();
+        AppContext::update_focus_scope(id);
+        self
+    }
+
+    /// Listen to an event in the capture phase, before the views inside this one get it.
+    /// Returning true stops the propagation of the event, so they don't get it at all. Use
+    /// [`on_event_phase`](Decorators::on_event_phase) to listen after them instead.
     fn on_event(self, listener: EventListner, action: impl Fn(&Event) -> bool + 'static) -> Self {
+        self.on_event_phase(listener, EventPhase::Capture, move |cx, event| {
+            if action(event) {
+                cx.stop_propagation();
+            }
+        })
+    }
+
+    /// Listen to an event in the given phase. Use [`EventCx::stop_propagation`] and
+    /// [`EventCx::prevent_default`] to control what happens to the event next.
+    fn on_event_phase(
+        self,
+        listener: EventListner,
+        phase: EventPhase,
+        action: impl Fn(&mut EventCx, &Event) + 'static,
+    ) -> Self {
         let id = self.id();
-        AppContext::update_event_listner(id, listener, Box::new(action));
+        AppContext::update_event_listner(id, listener, phase, Box::new(action));
+        self
+    }
+
+    /// Let the view be dragged with the mouse. `payload` is called when a drag starts, and what it
+    /// gives is dropped on a [`drop_target`](Decorators::drop_target) that accepts it. While
+    /// dragging, a copy of the view follows the mouse. The view stops being draggable when the
+    /// scope of `cx` is disposed.
+    fn draggable<P: 'static>(self, cx: AppContext, payload: impl Fn() -> P + 'static) -> Self {
+        let id = self.id();
+        AppContext::update_draggable(id, Box::new(move || Box::new(payload())));
+        on_cleanup(cx.scope, move || AppContext::remove_draggable(id));
+        self
+    }
+
+    /// Accept dragged views whose payload is a `P` that `accepts` allows. `on_drop` gets the
+    /// payload and where in the view it was dropped. The view gets [`Event::DragEnter`] and
+    /// [`Event::DragLeave`] when an accepted payload is dragged over it. The view stops being a
+    /// drop target when the scope of `cx` is disposed.
+    fn drop_target<P: 'static>(
+        self,
+        cx: AppContext,
+        accepts: impl Fn(&P) -> bool + 'static,
+        on_drop: impl Fn(P, Point) + 'static,
+    ) -> Self {
+        let id = self.id();
+        AppContext::update_drop_target(
+            id,
+            Box::new(move |payload| payload.downcast_ref().map(&accepts).unwrap_or(false)),
+            Box::new(move |payload, pos| {
+                if let Ok(payload) = payload.downcast() {
+                    on_drop(*payload, pos);
+                }
+            }),
+        );
+        on_cleanup(cx.scope, move || AppContext::remove_drop_target(id));
+        self
+    }
+
+    /// Run `action` with the position of the mouse when the view is clicked with the secondary
+    /// button, which is usually the right one
+    fn on_secondary_click(self, action: impl Fn(Point) + 'static) -> Self {
+        let id = self.id();
+        AppContext::update_gesture(id, GestureHandler::SecondaryClick(Box::new(action)));
+        self
+    }
+
+    /// Run `action` with the position of the press when the primary button is held down on the
+    /// view for [`LONG_PRESS_DURATION`] without moving
+    fn on_long_press(self, action: impl Fn(Point) + 'static) -> Self {
+        let id = self.id();
+        AppContext::update_gesture(
+            id,
+            GestureHandler::LongPress(LONG_PRESS_DURATION, Box::new(action)),
+        );
+        self
+    }
+
+    /// Follow drags of the mouse that start on the view, once the mouse moved
+    /// [`DRAG_THRESHOLD`](crate::gesture::DRAG_THRESHOLD) pixels
+    fn on_drag_gesture(self, action: impl Fn(&DragGesture) + 'static) -> Self {
+        let id = self.id();
+        AppContext::update_gesture(id, GestureHandler::Drag(Box::new(action)));
+        self
+    }
+
+    /// Run `action` with the velocity of the mouse, in pixels per second, when a drag on the view
+    /// is released while moving fast
+    fn on_fling(self, action: impl Fn(Vec2) + 'static) -> Self {
+        let id = self.id();
+        AppContext::update_gesture(id, GestureHandler::Fling(Box::new(action)));
         self
     }
 
@@ -38,6 +179,15 @@ pub trait Decorators: View + Sized {
         AppContext::update_resize_listner(id, Box::new(action));
         self
     }
+
+    /// Take text from the input method of the platform while the view has the keyboard focus.
+    /// `state` gives the text, selection and caret of the view for the input method, and the
+    /// typed and composed text comes to the view as composition events.
+    fn text_input(self, state: impl Fn() -> TextInputState + 'static) -> Self {
+        let id = self.id();
+        AppContext::update_text_input(id, Box::new(state));
+        self
+    }
 }
 
 impl<V: View> Decorators for V {}
@@ -4,7 +4,9 @@ use crate::{
     app::AppContext,
     context::{EventCx, UpdateCx},
     event::Event,
+    gesture::click_event,
     id::Id,
+    style::{CursorStyle, ReifiedStyle},
     view::{ChangeFlags, View},
 };
 
@@ -43,6 +45,10 @@ impl<V: View> View for DoubleClick<V> {
         }
     }
 
+    fn view_style(&self) -> Option<ReifiedStyle> {
+        Some(ReifiedStyle::default().cursor(CursorStyle::Pointer))
+    }
+
     fn update(&mut self, _cx: &mut UpdateCx, _state: Box<dyn Any>) -> ChangeFlags {
         ChangeFlags::empty()
     }
@@ -64,24 +70,15 @@ impl<V: View> View for DoubleClick<V> {
             }
         }
 
-        match &event {
-            Event::MouseDown(event) => {
-                if event.count == 2 {
-                    cx.update_active(self.id);
-                    true
-                } else {
-                    false
-                }
-            }
-            Event::MouseUp(event) => {
-                let rect = cx.get_size(self.id).unwrap_or_default().to_rect();
-                if rect.contains(event.pos) {
-                    (self.on_double_click)();
-                }
-                true
-            }
-            _ => false,
+        if cx.is_default_prevented() {
+            return false;
+        }
+
+        let (handled, clicked) = click_event(cx, self.id, &event, 2);
+        if clicked {
+            (self.on_double_click)();
         }
+        handled
     }
 
     fn paint(&mut self, cx: &mut crate::context::PaintCx) {
@@ -1,14 +1,12 @@
-use std::any::Any;
+use std::{any::Any, ops::Range};
 
 use crate::{
-    cosmic_text::{Attrs, AttrsList, FamilyOwned, TextLayout},
-    style::ReifiedStyle,
+    cosmic_text::{Attrs, AttrsList, FamilyOwned, LineHeightValue, TextLayout},
+    style::{ReifiedStyle, TextAlign, TextOverflow, WhiteSpace, DEFAULT_FONT_SIZE},
+    text::{draw_text, letter_spaced_index, letter_spaced_width, text_spacing, wrap_letter_spaced},
 };
-use floem_renderer::{
-    cosmic_text::{Style as FontStyle, Weight},
-    Renderer,
-};
-use glazier::kurbo::Point;
+use floem_renderer::cosmic_text::{Style as FontStyle, Weight};
+use glazier::kurbo::{Point, Rect};
 use leptos_reactive::create_effect;
 use taffy::{prelude::Node, style::Dimension};
 use vello::peniko::Color;
@@ -18,16 +16,19 @@ use crate::{
     context::{EventCx, UpdateCx},
     event::Event,
     id::Id,
-    style::Style,
+    style::{Length, Style},
     view::{ChangeFlags, View},
 };
 
 pub struct Label {
     id: Id,
     label: String,
+    /// The label with white space collapsed, unless it's preserved by [`WhiteSpace::Pre`]
+    text: String,
     text_layout: Option<TextLayout>,
     text_node: Option<Node>,
     available_text: Option<String>,
+    available_fade: Option<Range<usize>>,
     available_width: Option<f32>,
     available_text_layout: Option<TextLayout>,
     color: Option<Color>,
@@ -35,6 +36,10 @@ pub struct Label {
     font_family: Option<String>,
     font_weight: Option<Weight>,
     font_style: Option<FontStyle>,
+    line_height: Option<f32>,
+    letter_spacing: Option<f32>,
+    white_space: Option<WhiteSpace>,
+    text_overflow: Option<TextOverflow>,
 }
 
 pub fn label(cx: AppContext, label: impl Fn() -> String + 'static) -> Label {
@@ -46,9 +51,11 @@ pub fn label(cx: AppContext, label: impl Fn() -> String + 'static) -> Label {
     Label {
         id,
         label: "".to_string(),
+        text: "".to_string(),
         text_layout: None,
         text_node: None,
         available_text: None,
+        available_fade: None,
         available_width: None,
         available_text_layout: None,
         color: None,
@@ -56,13 +63,43 @@ pub fn label(cx: AppContext, label: impl Fn() -> String + 'static) -> Label {
         font_family: None,
         font_weight: None,
         font_style: None,
+        line_height: None,
+        letter_spacing: None,
+        white_space: None,
+        text_overflow: None,
     }
 }
 
 impl Label {
-    fn set_text_layout(&mut self) {
-        let mut text_layout = TextLayout::new();
-        let mut attrs = Attrs::new().color(self.color.unwrap_or(Color::BLACK));
+    fn white_space(&self) -> WhiteSpace {
+        self.white_space.unwrap_or(WhiteSpace::Pre)
+    }
+
+    fn text_overflow(&self) -> TextOverflow {
+        self.text_overflow.unwrap_or(TextOverflow::Ellipsis)
+    }
+
+    /// The width of the widest line of `layout` with the letter spacing
+    fn text_width(&self, layout: &TextLayout) -> f32 {
+        match self.letter_spacing {
+            Some(letter_spacing) => letter_spaced_width(layout, letter_spacing),
+            None => layout.size().width as f32,
+        }
+    }
+
+    /// The index of the text at `x` on the first line of `layout`
+    fn index_at(&self, layout: &TextLayout, x: f32) -> usize {
+        match self.letter_spacing {
+            Some(letter_spacing) => letter_spaced_index(layout, x, letter_spacing),
+            None => layout.hit_point(Point::new(x as f64, 0.0)).index,
+        }
+    }
+
+    /// Build the attributes for `text`, with the characters in `fade` getting more transparent
+    /// towards the end of the range.
+    fn get_attrs_list(&self, text: &str, fade: Option<Range<usize>>) -> AttrsList {
+        let color = self.color.unwrap_or(Color::BLACK);
+        let mut attrs = Attrs::new().color(color);
         if let Some(font_size) = self.font_size {
             attrs = attrs.font_size(font_size);
         }
@@ -79,31 +116,62 @@ impl Label {
         if let Some(font_weight) = self.font_weight {
             attrs = attrs.weight(font_weight);
         }
-        text_layout.set_text(self.label.as_str(), AttrsList::new(attrs));
-        self.text_layout = Some(text_layout);
+        if let Some(line_height) = self.line_height {
+            attrs = attrs.line_height(LineHeightValue::Normal(line_height));
+        }
+        let mut attrs_list = AttrsList::new(attrs);
 
-        if let Some(new_text) = self.available_text.as_ref() {
-            let mut text_layout = TextLayout::new();
-            let mut attrs = Attrs::new().color(self.color.unwrap_or(Color::BLACK));
-            if let Some(font_size) = self.font_size {
-                attrs = attrs.font_size(font_size);
-            }
-            if let Some(font_style) = self.font_style {
-                attrs = attrs.style(font_style);
+        if let Some(fade) = fade.filter(|fade| text.get(fade.clone()).is_some()) {
+            let starts: Vec<usize> = text[fade.clone()]
+                .char_indices()
+                .map(|(i, _)| fade.start + i)
+                .collect();
+            let n = starts.len();
+            for (i, start) in starts.iter().enumerate() {
+                let end = starts.get(i + 1).copied().unwrap_or(fade.end);
+                let alpha = 1.0 - (i + 1) as f32 / (n + 1) as f32;
+                attrs_list.add_span(*start..end, attrs.color(color.with_alpha_factor(alpha)));
             }
-            let font_family = self.font_family.as_ref().map(|font_family| {
-                let family: Vec<FamilyOwned> = FamilyOwned::parse_list(font_family).collect();
-                family
-            });
-            if let Some(font_family) = font_family.as_ref() {
-                attrs = attrs.family(font_family);
+        }
+        attrs_list
+    }
+
+    fn set_text_layout(&mut self) {
+        self.text = match self.white_space() {
+            WhiteSpace::Pre => self.label.clone(),
+            WhiteSpace::Wrap | WhiteSpace::NoWrap => {
+                self.label.split_whitespace().collect::<Vec<_>>().join(" ")
             }
-            if let Some(font_weight) = self.font_weight {
-                attrs = attrs.weight(font_weight);
+        };
+
+        let mut text_layout = TextLayout::new();
+        text_layout.set_text(&self.text, self.get_attrs_list(&self.text, None));
+
+        if self.white_space() == WhiteSpace::Wrap {
+            if let Some(width) = self.available_width {
+                let mut wrapped = text_layout.clone();
+                match self.letter_spacing {
+                    Some(letter_spacing) => wrap_letter_spaced(&mut wrapped, width, letter_spacing),
+                    None => wrapped.set_size(width, f32::MAX),
+                }
+                self.available_text_layout = Some(wrapped);
             }
-            text_layout.set_text(new_text, AttrsList::new(attrs));
-            self.available_text_layout = Some(text_layout);
+        } else if let Some(new_text) = self.available_text.as_ref() {
+            let mut available_text_layout = TextLayout::new();
+            available_text_layout.set_text(
+                new_text,
+                self.get_attrs_list(new_text, self.available_fade.clone()),
+            );
+            self.available_text_layout = Some(available_text_layout);
         }
+        self.text_layout = Some(text_layout);
+    }
+
+    fn clear_available(&mut self) {
+        self.available_text = None;
+        self.available_fade = None;
+        self.available_width = None;
+        self.available_text_layout = None;
     }
 }
 
@@ -120,6 +188,7 @@ impl View for Label {
         if let Ok(state) = state.downcast() {
             self.label = *state;
             self.text_layout = None;
+            self.clear_available();
             cx.request_layout(self.id());
             ChangeFlags::LAYOUT
         } else {
@@ -133,6 +202,13 @@ impl View for Label {
 
     fn layout(&mut self, cx: &mut crate::context::LayoutCx) -> taffy::prelude::Node {
         cx.layout_node(self.id, true, |cx| {
+            if self.white_space != cx.white_space || self.text_overflow != cx.text_overflow {
+                self.white_space = cx.white_space;
+                self.text_overflow = cx.text_overflow;
+                self.clear_available();
+                self.text_layout = None;
+            }
+
             let (width, height) = if self.label.is_empty() {
                 (0.0, cx.current_font_size().unwrap_or(12.0))
             } else {
@@ -140,11 +216,15 @@ impl View for Label {
                     || self.font_family.as_deref() != cx.current_font_family()
                     || self.font_weight != cx.font_weight
                     || self.font_style != cx.font_style
+                    || self.line_height != cx.line_height
+                    || self.letter_spacing != cx.letter_spacing
                 {
                     self.font_size = cx.current_font_size();
                     self.font_family = cx.current_font_family().map(|s| s.to_string());
                     self.font_weight = cx.font_weight;
                     self.font_style = cx.font_style;
+                    self.line_height = cx.line_height;
+                    self.letter_spacing = cx.letter_spacing;
                     self.set_text_layout();
                 }
                 if self.text_layout.is_none() {
@@ -152,8 +232,13 @@ impl View for Label {
                 }
                 let text_layout = self.text_layout.as_ref().unwrap();
                 let size = text_layout.size();
-                let width = size.width.ceil() as f32;
-                let height = size.height as f32;
+                let width = self.text_width(text_layout).ceil();
+                let height = match self.available_text_layout.as_ref() {
+                    Some(wrapped) if self.white_space() == WhiteSpace::Wrap => {
+                        wrapped.size().height as f32
+                    }
+                    _ => size.height as f32,
+                };
                 (width, height)
             };
 
@@ -167,11 +252,16 @@ impl View for Label {
             }
             let text_node = self.text_node.unwrap();
 
-            let style = Style::default()
+            let mut style = Style::default()
                 .width(Dimension::Points(width))
-                .height(Dimension::Points(height))
+                .height(Dimension::Points(height));
+            if self.white_space() == WhiteSpace::Wrap {
+                // Let the text shrink to the available width, it wraps in `compute_layout`
+                style = style.min_width(Length::Pt(0.0));
+            }
+            let style = style
                 .reify(&ReifiedStyle::default())
-                .to_taffy_style();
+                .to_taffy_style(&cx.length_context());
             let _ = cx.app_state.taffy.set_style(text_node, style);
 
             vec![text_node]
@@ -186,47 +276,67 @@ impl View for Label {
         let text_node = self.text_node.unwrap();
         let layout = cx.app_state.taffy.layout(text_node).unwrap();
         let text_layout = self.text_layout.as_ref().unwrap();
-        let width = text_layout.size().width as f32;
-        if width > layout.size.width {
-            if self.available_width != Some(layout.size.width) {
-                let mut dots_text = TextLayout::new();
-                let mut attrs = Attrs::new().color(self.color.unwrap_or(Color::BLACK));
-                if let Some(font_size) = self.font_size {
-                    attrs = attrs.font_size(font_size);
+        let width = self.text_width(text_layout);
+        if width <= layout.size.width {
+            if self.available_text_layout.is_some() {
+                if self.white_space() == WhiteSpace::Wrap {
+                    // The text fits on one line again, so its height changes back
+                    cx.app_state.request_layout(self.id);
                 }
-                if let Some(font_style) = self.font_style {
-                    attrs = attrs.style(font_style);
-                }
-                let font_family = self.font_family.as_ref().map(|font_family| {
-                    let family: Vec<FamilyOwned> = FamilyOwned::parse_list(font_family).collect();
-                    family
-                });
-                if let Some(font_family) = font_family.as_ref() {
-                    attrs = attrs.family(font_family);
-                }
-                if let Some(font_weight) = self.font_weight {
-                    attrs = attrs.weight(font_weight);
+                self.clear_available();
+            }
+            return;
+        }
+        if self.available_width == Some(layout.size.width) {
+            return;
+        }
+
+        match (self.white_space(), self.text_overflow()) {
+            (WhiteSpace::Wrap, _) => {
+                self.available_width = Some(layout.size.width);
+                self.set_text_layout();
+                let height = self
+                    .available_text_layout
+                    .as_ref()
+                    .map(|wrapped| wrapped.size().height as f32)
+                    .unwrap_or(layout.size.height);
+                if height != layout.size.height {
+                    // The text node needs the height of the wrapped lines
+                    cx.app_state.request_layout(self.id);
                 }
-                dots_text.set_text("...", AttrsList::new(attrs));
+            }
+            (_, TextOverflow::Clip) => {
+                // The full text is painted and clipped to the box
+                self.available_width = Some(layout.size.width);
+            }
+            (_, TextOverflow::Ellipsis) => {
+                let mut dots_text = TextLayout::new();
+                dots_text.set_text("...", self.get_attrs_list("...", None));
 
-                let dots_width = dots_text.size().width as f32;
+                let dots_width = self.text_width(&dots_text);
                 let width_left = layout.size.width - dots_width;
-                let hit_point = text_layout.hit_point(Point::new(width_left as f64, 0.0));
-                let index = hit_point.index;
+                let index = self.index_at(text_layout, width_left);
 
                 let new_text = if index > 0 {
-                    format!("{}...", &self.label[..index])
+                    format!("{}...", &self.text[..index])
                 } else {
                     "".to_string()
                 };
                 self.available_text = Some(new_text);
+                self.available_fade = None;
+                self.available_width = Some(layout.size.width);
+                self.set_text_layout();
+            }
+            (_, TextOverflow::Fade) => {
+                let fade_width =
+                    (self.font_size.unwrap_or(DEFAULT_FONT_SIZE) * 2.0).min(layout.size.width);
+                let fade_start = self.index_at(text_layout, layout.size.width - fade_width);
+                let fade_end = self.index_at(text_layout, layout.size.width);
+                self.available_text = Some(self.text.clone());
+                self.available_fade = Some(fade_start..fade_end.max(fade_start));
                 self.available_width = Some(layout.size.width);
                 self.set_text_layout();
             }
-        } else {
-            self.available_text = None;
-            self.available_width = None;
-            self.available_text_layout = None;
         }
     }
 
@@ -240,21 +350,48 @@ impl View for Label {
             || self.font_family.as_deref() != cx.font_family.as_deref()
             || self.font_weight != cx.font_weight
             || self.font_style != cx.font_style
+            || self.line_height != cx.line_height
+            || self.letter_spacing != cx.letter_spacing
         {
             self.color = cx.color;
             self.font_size = cx.font_size;
             self.font_family = cx.font_family.clone();
             self.font_weight = cx.font_weight;
             self.font_style = cx.font_style;
+            self.line_height = cx.line_height;
+            self.letter_spacing = cx.letter_spacing;
             self.set_text_layout();
         }
         let text_node = self.text_node.unwrap();
-        let location = cx.app_state.taffy.layout(text_node).unwrap().location;
-        let point = Point::new(location.x as f64, location.y as f64);
-        if let Some(text_layout) = self.available_text_layout.as_ref() {
-            cx.draw_text(text_layout, point);
-        } else {
-            cx.draw_text(self.text_layout.as_ref().unwrap(), point);
+        let layout = *cx.app_state.taffy.layout(text_node).unwrap();
+        let text_layout = self
+            .available_text_layout
+            .as_ref()
+            .unwrap_or_else(|| self.text_layout.as_ref().unwrap());
+
+        let spacing = text_spacing(
+            text_layout,
+            layout.size.width,
+            self.letter_spacing,
+            cx.text_align.unwrap_or(TextAlign::Start),
+        );
+        let point = Point::new(layout.location.x as f64, layout.location.y as f64);
+
+        match self.text_overflow() {
+            TextOverflow::Clip | TextOverflow::Fade => {
+                let rect = Rect::from_origin_size(
+                    (layout.location.x as f64, layout.location.y as f64),
+                    (layout.size.width as f64, layout.size.height as f64),
+                );
+                let rect = cx.clip.map(|clip| clip.intersect(rect)).unwrap_or(rect);
+                cx.save();
+                cx.clip(&rect);
+                draw_text(cx, text_layout, point, &spacing);
+                cx.restore();
+            }
+            TextOverflow::Ellipsis => {
+                draw_text(cx, text_layout, point, &spacing);
+            }
         }
     }
 }
@@ -28,6 +28,9 @@ pub use container_box::*;
 mod decorator;
 pub use decorator::*;
 
+mod context_menu;
+pub use context_menu::*;
+
 mod virtual_list;
 pub use virtual_list::*;
 
@@ -39,3 +42,6 @@ pub use tab::*;
 
 mod stack;
 pub use stack::*;
+
+mod tooltip;
+pub use tooltip::*;
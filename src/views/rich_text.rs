@@ -1,7 +1,7 @@
 use std::any::Any;
 
-use floem_renderer::{cosmic_text::TextLayout, Renderer};
-use glazier::kurbo::Point;
+use floem_renderer::cosmic_text::TextLayout;
+use glazier::kurbo::{Point, Rect};
 use leptos_reactive::create_effect;
 use taffy::{prelude::Node, style::Dimension};
 
@@ -10,14 +10,25 @@ use crate::{
     context::{EventCx, UpdateCx},
     event::Event,
     id::Id,
-    style::{ReifiedStyle, Style},
+    style::{Length, ReifiedStyle, Style, TextAlign, WhiteSpace},
+    text::{draw_text, letter_spaced_width, text_spacing, wrap_letter_spaced},
     view::{ChangeFlags, View},
 };
 
+/// A view of a [`TextLayout`] built by the caller.
+///
+/// Text that overflows the view is clipped, whatever its
+/// [`TextOverflow`](crate::style::TextOverflow) is. The view can't replace the end of the text
+/// with "..." or fade it out like [`Label`](crate::views::Label) does, since the text and its
+/// attributes are in the caller's layout.
 pub struct RichText {
     id: Id,
     text_layout: TextLayout,
     text_node: Option<Node>,
+    available_width: Option<f32>,
+    available_text_layout: Option<TextLayout>,
+    white_space: Option<WhiteSpace>,
+    letter_spacing: Option<f32>,
 }
 
 pub fn rich_text(cx: AppContext, text_layout: impl Fn() -> TextLayout + 'static) -> RichText {
@@ -31,6 +42,20 @@ pub fn rich_text(cx: AppContext, text_layout: impl Fn() -> TextLayout + 'static)
         id,
         text_layout: text,
         text_node: None,
+        available_width: None,
+        available_text_layout: None,
+        white_space: None,
+        letter_spacing: None,
+    }
+}
+
+impl RichText {
+    /// The width of the widest line of `layout` with the letter spacing
+    fn text_width(&self, layout: &TextLayout) -> f32 {
+        match self.letter_spacing {
+            Some(letter_spacing) => letter_spaced_width(layout, letter_spacing),
+            None => layout.size().width as f32,
+        }
     }
 }
 
@@ -46,6 +71,8 @@ impl View for RichText {
     fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn Any>) -> ChangeFlags {
         if let Ok(state) = state.downcast() {
             self.text_layout = *state;
+            self.available_width = None;
+            self.available_text_layout = None;
             cx.request_layout(self.id());
             ChangeFlags::LAYOUT
         } else {
@@ -59,9 +86,19 @@ impl View for RichText {
 
     fn layout(&mut self, cx: &mut crate::context::LayoutCx) -> taffy::prelude::Node {
         cx.layout_node(self.id, true, |cx| {
+            if self.white_space != cx.white_space || self.letter_spacing != cx.letter_spacing {
+                self.white_space = cx.white_space;
+                self.letter_spacing = cx.letter_spacing;
+                self.available_width = None;
+                self.available_text_layout = None;
+            }
             let size = self.text_layout.size();
-            let width = size.width as f32;
-            let height = size.height as f32;
+            let width = self.text_width(&self.text_layout);
+            let height = self
+                .available_text_layout
+                .as_ref()
+                .map(|wrapped| wrapped.size().height)
+                .unwrap_or(size.height) as f32;
 
             if self.text_node.is_none() {
                 self.text_node = Some(
@@ -73,22 +110,76 @@ impl View for RichText {
             }
             let text_node = self.text_node.unwrap();
 
-            let style = Style::default()
+            let mut style = Style::default()
                 .width(Dimension::Points(width))
-                .height(Dimension::Points(height))
+                .height(Dimension::Points(height));
+            if self.white_space == Some(WhiteSpace::Wrap) {
+                style = style.min_width(Length::Pt(0.0));
+            }
+            let style = style
                 .reify(&ReifiedStyle::default())
-                .to_taffy_style();
+                .to_taffy_style(&cx.length_context());
             let _ = cx.app_state.taffy.set_style(text_node, style);
             vec![text_node]
         })
     }
 
-    fn compute_layout(&mut self, _cx: &mut crate::context::LayoutCx) {}
+    fn compute_layout(&mut self, cx: &mut crate::context::LayoutCx) {
+        if self.white_space != Some(WhiteSpace::Wrap) {
+            return;
+        }
+
+        let text_node = self.text_node.unwrap();
+        let layout = *cx.app_state.taffy.layout(text_node).unwrap();
+        let width = self.text_width(&self.text_layout);
+        if width <= layout.size.width {
+            if self.available_text_layout.is_some() {
+                self.available_width = None;
+                self.available_text_layout = None;
+                cx.app_state.request_layout(self.id);
+            }
+        } else if self.available_width != Some(layout.size.width) {
+            let mut wrapped = self.text_layout.clone();
+            match self.letter_spacing {
+                Some(letter_spacing) => {
+                    wrap_letter_spaced(&mut wrapped, layout.size.width, letter_spacing)
+                }
+                None => wrapped.set_size(layout.size.width, f32::MAX),
+            }
+            if wrapped.size().height as f32 != layout.size.height {
+                // The text node needs the height of the wrapped lines
+                cx.app_state.request_layout(self.id);
+            }
+            self.available_width = Some(layout.size.width);
+            self.available_text_layout = Some(wrapped);
+        }
+    }
 
     fn paint(&mut self, cx: &mut crate::context::PaintCx) {
         let text_node = self.text_node.unwrap();
-        let location = cx.app_state.taffy.layout(text_node).unwrap().location;
-        let point = Point::new(location.x as f64, location.y as f64);
-        cx.draw_text(&self.text_layout, point);
+        let layout = *cx.app_state.taffy.layout(text_node).unwrap();
+        let text_layout = self
+            .available_text_layout
+            .as_ref()
+            .unwrap_or(&self.text_layout);
+
+        let spacing = text_spacing(
+            text_layout,
+            layout.size.width,
+            self.letter_spacing,
+            cx.text_align.unwrap_or(TextAlign::Start),
+        );
+        let point = Point::new(layout.location.x as f64, layout.location.y as f64);
+
+        // Every text overflow clips the text, see the docs of `RichText`
+        let rect = Rect::from_origin_size(
+            (layout.location.x as f64, layout.location.y as f64),
+            (layout.size.width as f64, layout.size.height as f64),
+        );
+        let rect = cx.clip.map(|clip| clip.intersect(rect)).unwrap_or(rect);
+        cx.save();
+        cx.clip(&rect);
+        draw_text(cx, text_layout, point, &spacing);
+        cx.restore();
     }
 }
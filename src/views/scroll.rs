@@ -431,13 +431,13 @@ impl<V: View> View for Scroll<V> {
     fn layout(&mut self, cx: &mut crate::context::LayoutCx) -> taffy::prelude::Node {
         cx.layout_node(self.id, true, |cx| {
             let child_id = self.child.id();
-            let child_intr_state = cx.app_state.get_interact_state(&child_id);
-            let mut child_view = cx.app_state.view_state(child_id);
-            child_view.style.position = StyleValue::Val(Position::Absolute);
-            // Update the reified style
-            child_view.reified_style = None;
-            let child_view_style = self.child.view_style().unwrap_or_default();
-            child_view.fill_reified_style(child_intr_state, &child_view_style);
+            let child_view = cx.app_state.view_state(child_id);
+            if child_view.style.position != StyleValue::Val(Position::Absolute) {
+                child_view.style.position = StyleValue::Val(Position::Absolute);
+                // Update the reified style
+                child_view.request_style = true;
+            }
+            cx.get_reified_style(|| self.child.view_style().unwrap_or_default(), child_id);
 
             let child_node = self.child.layout_main(cx);
 
@@ -447,7 +447,7 @@ impl<V: View> View for Scroll<V> {
                 .min_width(Dimension::Points(0.0))
                 .min_height(Dimension::Points(0.0))
                 .reify(&ReifiedStyle::default())
-                .to_taffy_style();
+                .to_taffy_style(&cx.length_context());
             if self.virtual_node.is_none() {
                 self.virtual_node =
                     Some(cx.app_state.taffy.new_leaf(virtual_style.clone()).unwrap());
@@ -541,7 +541,12 @@ impl<V: View> View for Scroll<V> {
         }
 
         if let Event::MouseWheel(mouse_event) = event {
-            self.clamp_child_viewport(cx.app_state, self.child_viewport + mouse_event.wheel_delta);
+            if !cx.is_default_prevented() {
+                self.clamp_child_viewport(
+                    cx.app_state,
+                    self.child_viewport + mouse_event.wheel_delta,
+                );
+            }
         }
 
         true
@@ -50,18 +50,15 @@ impl<VT: ViewTuple + 'static> View for Stack<VT> {
         id_path: Option<&[Id]>,
         event: crate::event::Event,
     ) -> bool {
-        let mut handled = false;
-        self.children.foreach_rev(&mut |view| {
+        let mut children = self.children.views_mut();
+        cx.app_state.sort_by_z_index(&mut children);
+        for view in children.into_iter().rev() {
             let id = view.id();
-            if cx.should_send(id, &event) {
-                handled = view.event_main(cx, id_path, event.clone());
-                if handled {
-                    return true;
-                }
+            if cx.should_send(id, &event) && view.event_main(cx, id_path, event.clone()) {
+                return true;
             }
-            false
-        });
-        handled
+        }
+        false
     }
 
     fn layout(&mut self, cx: &mut crate::context::LayoutCx) -> taffy::prelude::Node {
@@ -84,9 +81,10 @@ impl<VT: ViewTuple + 'static> View for Stack<VT> {
     }
 
     fn paint(&mut self, cx: &mut crate::context::PaintCx) {
-        self.children.foreach(&mut |view| {
+        let mut children = self.children.views_mut();
+        cx.app_state.sort_by_z_index(&mut children);
+        for view in children {
             view.paint_main(cx);
-            false
-        });
+        }
     }
 }
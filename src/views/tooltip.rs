@@ -0,0 +1,274 @@
+use std::time::Duration;
+
+use glazier::kurbo::{Point, Rect, Size};
+use taffy::style::AvailableSpace;
+
+use crate::{
+    app::AppContext,
+    context::{EventCx, LayoutCx, PaintCx, UpdateCx},
+    event::Event,
+    id::Id,
+    timer::TimerToken,
+    view::{ChangeFlags, View},
+};
+
+/// How long the pointer has to rest on the view before its tooltip shows
+pub const TOOLTIP_DELAY: Duration = Duration::from_millis(600);
+/// The space between a tooltip and what it is placed next to
+const TOOLTIP_GAP: f64 = 4.0;
+/// Roughly the height of the mouse cursor, which a tooltip at the pointer shouldn't cover
+const CURSOR_HEIGHT: f64 = 16.0;
+
+/// Where a tooltip is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TooltipPlacement {
+    /// Below the pointer, where it was when the tooltip showed
+    Pointer,
+    /// Below the view, aligned with its start
+    Anchored,
+}
+
+/// Sent by the timer when the pointer rested on the view long enough
+struct ShowTooltip;
+
+/// Shows a floating view after the pointer rested on the child for [`TOOLTIP_DELAY`].
+/// It is hidden when the pointer leaves the child or the child is clicked.
+pub struct Tooltip<V: View, T: View> {
+    id: Id,
+    child: V,
+    content: Option<T>,
+    content_fn: Box<dyn Fn(AppContext) -> T>,
+    cx: AppContext,
+    placement: TooltipPlacement,
+    shown: bool,
+    /// Clicking hides the tooltip until the pointer leaves the view
+    dismissed: bool,
+    timer: Option<TimerToken>,
+    /// The last position of the pointer on the view
+    pointer: Point,
+    window_origin: Point,
+}
+
+pub fn tooltip<V: View, T: View + 'static>(
+    cx: AppContext,
+    child: impl FnOnce(AppContext) -> V,
+    content: impl Fn(AppContext) -> T + 'static,
+) -> Tooltip<V, T> {
+    let id = cx.new_id();
+    let mut child_cx = cx;
+    child_cx.id = id;
+    let child = child(child_cx);
+    Tooltip {
+        id,
+        child,
+        content: None,
+        content_fn: Box::new(content),
+        cx: child_cx,
+        placement: TooltipPlacement::Pointer,
+        shown: false,
+        dismissed: false,
+        timer: None,
+        pointer: Point::ZERO,
+        window_origin: Point::ZERO,
+    }
+}
+
+impl<V: View, T: View> Tooltip<V, T> {
+    pub fn placement(mut self, placement: TooltipPlacement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    fn cancel_timer(&mut self) {
+        if let Some(timer) = self.timer.take() {
+            timer.cancel();
+        }
+    }
+
+    fn hide(&mut self, cx: &mut EventCx) {
+        self.cancel_timer();
+        if self.shown {
+            self.shown = false;
+            if let Some(content) = self.content.as_ref() {
+                cx.app_state.hide_overlay(content.id());
+            }
+            cx.app_state.request_layout(self.id);
+        }
+    }
+}
+
+impl<V: View, T: View> View for Tooltip<V, T> {
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn child(&mut self, id: Id) -> Option<&mut dyn View> {
+        if self.child.id() == id {
+            Some(&mut self.child)
+        } else {
+            match self.content.as_mut() {
+                Some(content) if content.id() == id => Some(content),
+                _ => None,
+            }
+        }
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn std::any::Any>) -> ChangeFlags {
+        if state.downcast::<ShowTooltip>().is_err() {
+            return ChangeFlags::empty();
+        }
+        self.timer = None;
+        if self.shown || self.dismissed {
+            return ChangeFlags::empty();
+        }
+        if self.content.is_none() {
+            self.content = Some((self.content_fn)(self.cx));
+        }
+        self.shown = true;
+        cx.request_layout(self.id);
+        ChangeFlags::LAYOUT
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx) -> taffy::prelude::Node {
+        cx.layout_node(self.id, true, |cx| {
+            if self.shown {
+                if let Some(content) = self.content.as_mut() {
+                    // The content is laid out on its own, so it isn't constrained by where the
+                    // child is
+                    content.layout_main(cx);
+                }
+            }
+            vec![self.child.layout_main(cx)]
+        })
+    }
+
+    fn compute_layout(&mut self, cx: &mut LayoutCx) {
+        self.window_origin = cx.window_origin;
+        self.child.compute_layout_main(cx);
+
+        if !self.shown {
+            return;
+        }
+        if let Some(content) = self.content.as_mut() {
+            let node = cx.app_state.view_state(content.id()).node;
+            let _ = cx.app_state.taffy.compute_layout(
+                node,
+                taffy::prelude::Size {
+                    width: AvailableSpace::MaxContent,
+                    height: AvailableSpace::MaxContent,
+                },
+            );
+            let size = cx
+                .app_state
+                .get_layout(content.id())
+                .map(|layout| Size::new(layout.size.width as f64, layout.size.height as f64));
+            let anchor = match self.placement {
+                TooltipPlacement::Pointer => Rect::from_origin_size(
+                    self.window_origin + self.pointer.to_vec2(),
+                    Size::new(0.0, CURSOR_HEIGHT),
+                ),
+                TooltipPlacement::Anchored => {
+                    let size = cx.app_state.get_layout(self.id).map(|layout| {
+                        Size::new(layout.size.width as f64, layout.size.height as f64)
+                    });
+                    Rect::from_origin_size(self.window_origin, size.unwrap_or_default())
+                }
+            };
+            let origin = floating_origin(
+                anchor,
+                size.unwrap_or_default(),
+                cx.app_state.root_size,
+                TOOLTIP_GAP,
+            );
+            cx.app_state.show_overlay(content.id(), origin);
+
+            cx.save();
+            cx.viewport = None;
+            cx.window_origin = origin;
+            content.compute_layout_main(cx);
+            cx.restore();
+        }
+    }
+
+    fn event(&mut self, cx: &mut EventCx, id_path: Option<&[Id]>, event: Event) -> bool {
+        if id_path.is_none() {
+            match &event {
+                Event::MouseMove(mouse_event) => {
+                    if cx.app_state.hovered.contains(&self.id) {
+                        self.pointer = mouse_event.pos;
+                        if !self.shown && !self.dismissed && self.timer.is_none() {
+                            let id = self.id;
+                            // The timer is cancelled with the scope of the view
+                            self.timer = Some(self.cx.request_timer(TOOLTIP_DELAY, move || {
+                                AppContext::update_state(id, ShowTooltip, false)
+                            }));
+                        }
+                    } else {
+                        self.dismissed = false;
+                        self.hide(cx);
+                    }
+                }
+                Event::WindowMouseLeave => {
+                    self.dismissed = false;
+                    self.hide(cx);
+                }
+                Event::MouseDown(_) | Event::MouseWheel(_) => {
+                    self.dismissed = true;
+                    self.hide(cx);
+                }
+                _ => {}
+            }
+        }
+        self.child.event_main(cx, id_path, event)
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        // The content is painted on top of the window as an overlay
+        self.child.paint_main(cx);
+    }
+}
+
+/// Where to put a floating view of `size` next to `anchor` in the window: below it and aligned
+/// with its start, or flipped above it and aligned with its end when it would go past the
+/// bottom or the right edge of the window
+pub(crate) fn floating_origin(anchor: Rect, size: Size, window: Size, gap: f64) -> Point {
+    let mut x = anchor.x0;
+    if x + size.width > window.width {
+        x = anchor.x1 - size.width;
+    }
+    let mut y = anchor.y1 + gap;
+    if y + size.height > window.height {
+        y = anchor.y0 - gap - size.height;
+    }
+    Point::new(x.max(0.0), y.max(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use glazier::kurbo::{Point, Rect, Size};
+
+    use super::floating_origin;
+
+    #[test]
+    fn floating_origin_flips_at_window_edges() {
+        let window = Size::new(200.0, 100.0);
+        let size = Size::new(50.0, 20.0);
+
+        let anchor = Rect::new(10.0, 10.0, 30.0, 20.0);
+        assert_eq!(
+            floating_origin(anchor, size, window, 4.0),
+            Point::new(10.0, 24.0)
+        );
+
+        // Too close to the bottom right, so it goes above and ends with the anchor
+        let anchor = Rect::new(170.0, 80.0, 190.0, 90.0);
+        assert_eq!(
+            floating_origin(anchor, size, window, 4.0),
+            Point::new(140.0, 56.0)
+        );
+
+        // Larger than the window, so it stays inside at the top left
+        let size = Size::new(300.0, 200.0);
+        assert_eq!(floating_origin(anchor, size, window, 4.0), Point::ZERO);
+    }
+}
@@ -322,22 +322,30 @@ where
         id_path: Option<&[Id]>,
         event: crate::event::Event,
     ) -> bool {
-        for child in self.children.iter_mut() {
-            if let Some((child, _)) = child.as_mut() {
-                let id = child.id();
-                if cx.should_send(id, &event) && child.event_main(cx, id_path, event.clone()) {
-                    return true;
-                }
+        let mut children = self
+            .children
+            .iter_mut()
+            .filter_map(|child| Some(&mut child.as_mut()?.0 as &mut dyn View))
+            .collect::<Vec<_>>();
+        cx.app_state.sort_by_z_index(&mut children);
+        for child in children.into_iter().rev() {
+            let id = child.id();
+            if cx.should_send(id, &event) && child.event_main(cx, id_path, event.clone()) {
+                return true;
             }
         }
         false
     }
 
     fn paint(&mut self, cx: &mut crate::context::PaintCx) {
-        for child in &mut self.children {
-            if let Some((child, _)) = child.as_mut() {
-                child.paint_main(cx);
-            }
+        let mut children = self
+            .children
+            .iter_mut()
+            .filter_map(|child| Some(&mut child.as_mut()?.0 as &mut dyn View))
+            .collect::<Vec<_>>();
+        cx.app_state.sort_by_z_index(&mut children);
+        for child in children {
+            child.paint_main(cx);
         }
     }
 }
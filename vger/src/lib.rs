@@ -1,6 +1,6 @@
 use anyhow::Result;
 use floem_renderer::cosmic_text::{SubpixelBin, SwashCache, SwashImage, TextLayout};
-use floem_renderer::{usvg, Renderer};
+use floem_renderer::{usvg, Renderer, TextSpacing};
 use peniko::{
     kurbo::{Affine, Point, Rect, Shape, Vec2},
     BrushRef, Color, GradientKind,
@@ -201,12 +201,21 @@ impl Renderer for VgerRenderer {
     }
 
     fn draw_text(&mut self, layout: &TextLayout, pos: impl Into<Point>) {
+        self.draw_text_with_spacing(layout, pos, &TextSpacing::default());
+    }
+
+    fn draw_text_with_spacing(
+        &mut self,
+        layout: &TextLayout,
+        pos: impl Into<Point>,
+        spacing: &TextSpacing,
+    ) {
         let mut swash_cache = SwashCache::new();
         let transform = self.transform.as_coeffs();
         let offset = Vec2::new(transform[4], transform[5]);
         let pos: Point = pos.into();
         let clip = self.clip;
-        for line in layout.layout_runs() {
+        for (line_index, line) in layout.layout_runs().enumerate() {
             if let Some(rect) = clip {
                 let y = pos.y + offset.y + line.line_y as f64;
                 if y + (line.line_height as f64) < rect.y0 {
@@ -216,8 +225,20 @@ impl Renderer for VgerRenderer {
                     break;
                 }
             }
-            'line_loop: for glyph_run in line.glyphs {
-                let x = glyph_run.x + pos.x as f32 + offset.x as f32;
+            let mut spaces = 0;
+            'line_loop: for (i, glyph_run) in line.glyphs.iter().enumerate() {
+                let x = glyph_run.x
+                    + spacing.glyph_offset(line_index, i, spaces) as f32
+                    + pos.x as f32
+                    + offset.x as f32;
+                if line
+                    .text
+                    .get(glyph_run.start..glyph_run.end)
+                    .map(|text| !text.is_empty() && text.chars().all(char::is_whitespace))
+                    .unwrap_or(false)
+                {
+                    spaces += 1;
+                }
                 let y = line.line_y + pos.y as f32 + offset.y as f32;
 
                 if let Some(rect) = clip {